@@ -0,0 +1,415 @@
+use std::fmt;
+
+use bevy::{prelude::*, utils::HashMap};
+use leafwing_input_manager::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    clock::Phone,
+    input::PlayerAction,
+    inventory::Belt,
+    money::{Money, Ratio, Rounding, TransactionCategory, Wallet},
+    notifications::{Notification, PostNotification},
+    player::Player,
+    GameState, PlayerSet,
+};
+
+pub struct AmmoPlugin;
+
+impl Plugin for AmmoPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<VendorState>()
+            .add_event::<UseVendor>()
+            .init_resource::<AmmoStock>()
+            .init_resource::<VendorCursor>()
+            .configure_set(
+                Update,
+                PlayerSet::Combat.run_if(in_state(VendorState::Closed)),
+            )
+            .configure_set(
+                Update,
+                PlayerSet::Movement.run_if(in_state(VendorState::Closed)),
+            )
+            .add_systems(OnEnter(VendorState::Open), spawn_vendor_ui)
+            .add_systems(OnExit(VendorState::Open), despawn_vendor_ui)
+            .add_systems(Update, select_ammo_type.in_set(PlayerSet::Combat))
+            .add_systems(
+                Update,
+                (
+                    open_vendor.run_if(in_state(VendorState::Closed)),
+                    (vendor_input, update_vendor_ui)
+                        .chain()
+                        .run_if(in_state(VendorState::Open)),
+                )
+                    .run_if(in_state(GameState::RunAndGun)),
+            );
+    }
+}
+
+/// Reloading with rounds that aren't in stock buys them on the spot for this much more
+const FIELD_MARKUP: (i64, i64) = (3, 2);
+/// Vendors knock this much off the per-round price for buying a whole box
+const VENDOR_DISCOUNT: (i64, i64) = (9, 10);
+
+/// Which rounds a gun takes
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Caliber {
+    #[default]
+    Rifle,
+    Pistol,
+    Shell,
+    Nail,
+}
+
+impl Caliber {
+    /// What one standard round costs at a vendor before any discount
+    pub fn round_price(&self) -> Money {
+        match self {
+            Caliber::Rifle => Money::from_cents(35),
+            Caliber::Pistol => Money::from_cents(30),
+            Caliber::Shell => Money::from_cents(100),
+            Caliber::Nail => Money::from_cents(8),
+        }
+    }
+}
+
+impl fmt::Display for Caliber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Caliber::Rifle => write!(f, "rifle"),
+            Caliber::Pistol => write!(f, "pistol"),
+            Caliber::Shell => write!(f, "shell"),
+            Caliber::Nail => write!(f, "nail"),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AmmoType {
+    #[default]
+    Standard,
+    /// Hits soft targets harder but does poorly against armor
+    HollowPoint,
+    ArmorPiercing,
+    /// Cheap hand loads that don't hit as hard
+    Reloaded,
+}
+
+impl AmmoType {
+    pub const ALL: [AmmoType; 4] = [
+        AmmoType::Standard,
+        AmmoType::HollowPoint,
+        AmmoType::ArmorPiercing,
+        AmmoType::Reloaded,
+    ];
+
+    pub fn damage(&self) -> f32 {
+        match self {
+            AmmoType::Standard => 1.0,
+            AmmoType::HollowPoint => 1.3,
+            AmmoType::ArmorPiercing => 0.9,
+            AmmoType::Reloaded => 0.8,
+        }
+    }
+
+    /// Subtracted from a target's armor, and how much material a shot can punch through
+    pub fn penetration(&self) -> f32 {
+        match self {
+            AmmoType::Standard => 0.0,
+            AmmoType::HollowPoint => -0.25,
+            AmmoType::ArmorPiercing => 0.5,
+            AmmoType::Reloaded => -0.1,
+        }
+    }
+
+    fn price(&self) -> Ratio {
+        match self {
+            AmmoType::Standard => Ratio::ONE,
+            AmmoType::HollowPoint => Ratio::new(8, 5),
+            AmmoType::ArmorPiercing => Ratio::new(2, 1),
+            AmmoType::Reloaded => Ratio::new(3, 5),
+        }
+    }
+
+    /// What one round of this type in `caliber` costs at a vendor
+    pub fn round_price(&self, caliber: Caliber) -> Money {
+        caliber
+            .round_price()
+            .mul_ratio(self.price(), Rounding::Ceil)
+    }
+
+    /// What one round costs when it has to be bought in the middle of a reload
+    pub fn field_price(&self, caliber: Caliber) -> Money {
+        let (numerator, denominator) = FIELD_MARKUP;
+        self.round_price(caliber)
+            .mul_ratio(Ratio::new(numerator, denominator), Rounding::Ceil)
+    }
+
+    fn next(&self) -> AmmoType {
+        let index = AmmoType::ALL
+            .iter()
+            .position(|ammo| ammo == self)
+            .unwrap_or(0);
+        AmmoType::ALL[(index + 1) % AmmoType::ALL.len()]
+    }
+}
+
+impl fmt::Display for AmmoType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AmmoType::Standard => write!(f, "Standard"),
+            AmmoType::HollowPoint => write!(f, "Hollow-point"),
+            AmmoType::ArmorPiercing => write!(f, "AP"),
+            AmmoType::Reloaded => write!(f, "Reloaded"),
+        }
+    }
+}
+
+/// Loose rounds the player carries, by caliber and type
+#[derive(Resource)]
+pub struct AmmoStock(HashMap<(Caliber, AmmoType), u32>);
+
+impl Default for AmmoStock {
+    fn default() -> Self {
+        let mut stock = AmmoStock(HashMap::default());
+        stock.add(Caliber::Rifle, AmmoType::Standard, 60);
+        stock.add(Caliber::Pistol, AmmoType::Standard, 24);
+        stock.add(Caliber::Shell, AmmoType::Standard, 12);
+        stock.add(Caliber::Nail, AmmoType::Standard, 80);
+        stock
+    }
+}
+
+impl AmmoStock {
+    pub fn count(&self, caliber: Caliber, ammo: AmmoType) -> u32 {
+        self.0.get(&(caliber, ammo)).copied().unwrap_or(0)
+    }
+
+    pub fn add(&mut self, caliber: Caliber, ammo: AmmoType, rounds: u32) {
+        *self.0.entry((caliber, ammo)).or_default() += rounds;
+    }
+
+    /// Takes up to `rounds` and returns how many there were
+    pub fn take(&mut self, caliber: Caliber, ammo: AmmoType, rounds: u32) -> u32 {
+        let count = self.0.entry((caliber, ammo)).or_default();
+        let taken = rounds.min(*count);
+        *count -= taken;
+        taken
+    }
+}
+
+/// A box of rounds a vendor sells
+#[derive(Clone, Copy, Debug)]
+pub struct AmmoListing {
+    pub caliber: Caliber,
+    pub ammo_type: AmmoType,
+    pub rounds: u32,
+}
+
+impl AmmoListing {
+    pub fn new(caliber: Caliber, ammo_type: AmmoType, rounds: u32) -> Self {
+        AmmoListing {
+            caliber,
+            ammo_type,
+            rounds,
+        }
+    }
+
+    pub fn price(&self) -> Money {
+        let (numerator, denominator) = VENDOR_DISCOUNT;
+        (self.ammo_type.round_price(self.caliber) * self.rounds as i64)
+            .mul_ratio(Ratio::new(numerator, denominator), Rounding::Ceil)
+    }
+}
+
+#[derive(Component)]
+pub struct AmmoVendor {
+    pub name: String,
+    pub listings: Vec<AmmoListing>,
+}
+
+#[derive(States, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VendorState {
+    #[default]
+    Closed,
+    Open,
+}
+
+/// Sent when the player interacts with an `AmmoVendor`
+#[derive(Event)]
+pub struct UseVendor(pub Entity);
+
+#[derive(Resource, Default)]
+struct VendorCursor {
+    vendor: Option<Entity>,
+    selected: usize,
+}
+
+#[derive(Component)]
+struct VendorUi;
+
+#[derive(Component)]
+struct VendorText;
+
+/// Picks the ammo type the active gun loads on its next reload
+fn select_ammo_type(
+    mut belt: ResMut<Belt>,
+    player_query: Query<&ActionState<PlayerAction>, With<Player>>,
+) {
+    let Ok(action) = player_query.get_single() else {
+        return;
+    };
+    if action.just_pressed(PlayerAction::CycleAmmo) && !belt.is_empty() {
+        let gun = belt.gun_mut();
+        let next = gun.ammo_type().next();
+        gun.select_ammo_type(next);
+    }
+}
+
+fn open_vendor(
+    mut use_events: EventReader<UseVendor>,
+    mut cursor: ResMut<VendorCursor>,
+    mut next_state: ResMut<NextState<VendorState>>,
+) {
+    if let Some(UseVendor(vendor)) = use_events.iter().last() {
+        *cursor = VendorCursor {
+            vendor: Some(*vendor),
+            selected: 0,
+        };
+        next_state.set(VendorState::Open);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn vendor_input(
+    mut commands: Commands,
+    player_query: Query<&ActionState<PlayerAction>, With<Player>>,
+    vendor_query: Query<&AmmoVendor>,
+    mut cursor: ResMut<VendorCursor>,
+    mut stock: ResMut<AmmoStock>,
+    mut wallet: ResMut<Wallet>,
+    phone: Res<Phone>,
+    mut next_state: ResMut<NextState<VendorState>>,
+) {
+    let Ok(action) = player_query.get_single() else {
+        return;
+    };
+    let Some(vendor) = cursor
+        .vendor
+        .and_then(|vendor| vendor_query.get(vendor).ok())
+    else {
+        next_state.set(VendorState::Closed);
+        return;
+    };
+
+    if action.just_pressed(PlayerAction::PhoneBack) || action.just_pressed(PlayerAction::Interact) {
+        next_state.set(VendorState::Closed);
+        return;
+    }
+    if action.just_pressed(PlayerAction::PhoneUp) {
+        cursor.selected = cursor.selected.saturating_sub(1);
+    }
+    if action.just_pressed(PlayerAction::PhoneDown) {
+        cursor.selected = (cursor.selected + 1).min(vendor.listings.len().saturating_sub(1));
+    }
+
+    if !action.just_pressed(PlayerAction::PhoneSelect) {
+        return;
+    }
+    let Some(listing) = vendor.listings.get(cursor.selected) else {
+        return;
+    };
+    let price = listing.price();
+    if wallet.funds() < price {
+        commands.add(PostNotification(Notification::new(
+            "Can't afford",
+            format!("{} wants {} for that", vendor.name, price),
+        )));
+        return;
+    }
+
+    stock.add(listing.caliber, listing.ammo_type, listing.rounds);
+    commands.add(wallet.debit(
+        price,
+        TransactionCategory::Ammo,
+        phone.date,
+        format!(
+            "{} {} {} rounds",
+            listing.rounds, listing.ammo_type, listing.caliber
+        ),
+    ));
+}
+
+fn spawn_vendor_ui(mut commands: Commands) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(15.0),
+                left: Val::Percent(25.0),
+                width: Val::Percent(50.0),
+                padding: UiRect::all(Val::Px(20.0)),
+                ..default()
+            },
+            background_color: Color::rgba(0.05, 0.05, 0.05, 0.85).into(),
+            z_index: ZIndex::Global(40),
+            ..default()
+        })
+        .insert(VendorUi)
+        .with_children(|parent| {
+            parent
+                .spawn(TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 22.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ))
+                .insert(VendorText);
+        });
+}
+
+fn despawn_vendor_ui(mut commands: Commands, ui_query: Query<Entity, With<VendorUi>>) {
+    for entity in &ui_query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn update_vendor_ui(
+    cursor: Res<VendorCursor>,
+    stock: Res<AmmoStock>,
+    wallet: Res<Wallet>,
+    vendor_query: Query<&AmmoVendor>,
+    mut text_query: Query<&mut Text, With<VendorText>>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+    let Some(vendor) = cursor
+        .vendor
+        .and_then(|vendor| vendor_query.get(vendor).ok())
+    else {
+        return;
+    };
+
+    let mut lines = vec![
+        format!("{}  (you have {})", vendor.name, wallet.funds()),
+        String::new(),
+    ];
+    for (index, listing) in vendor.listings.iter().enumerate() {
+        let mark = if index == cursor.selected { ">" } else { " " };
+        lines.push(format!(
+            "{} {} x{} {}: {}  (have {})",
+            mark,
+            listing.ammo_type,
+            listing.rounds,
+            listing.caliber,
+            listing.price(),
+            stock.count(listing.caliber, listing.ammo_type),
+        ));
+    }
+    lines.push(String::new());
+    lines.push("[^/v] Choose  [Select] Buy  [Back] Leave".to_string());
+    text.sections[0].value = lines.join("\n");
+}
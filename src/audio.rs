@@ -1,5 +1,7 @@
 use bevy::{audio::Volume, prelude::*};
 
+use crate::weapon::Gun;
+
 pub struct AudioPlugin;
 
 impl Plugin for AudioPlugin {
@@ -20,7 +22,8 @@ pub struct SoundBank {
 }
 
 impl SoundBank {
-    pub fn bullet_shot(&self) -> ShotBundle {
+    /// Plays `gun`'s own shot sound, or the default one if its definition doesn't have one
+    pub fn bullet_shot(&self, gun: &Gun) -> ShotBundle {
         use rand::{thread_rng, Rng};
 
         let mut rng = thread_rng();
@@ -28,7 +31,7 @@ impl SoundBank {
         let volume = rng.gen_range(0.8..1.1);
         (
             AudioSourceBundle {
-                source: self.gun_shot.clone(),
+                source: gun.shot_sound().unwrap_or(&self.gun_shot).clone(),
                 settings: PlaybackSettings::DESPAWN
                     .with_volume(Volume::new_relative(volume))
                     .with_speed(speed),
@@ -37,7 +40,7 @@ impl SoundBank {
         )
     }
 
-    pub fn empty_fire(&self) -> EmptyBundle {
+    pub fn empty_fire(&self, gun: &Gun) -> EmptyBundle {
         use rand::{thread_rng, Rng};
 
         let mut rng = thread_rng();
@@ -46,7 +49,7 @@ impl SoundBank {
 
         (
             AudioSourceBundle {
-                source: self.gun_empty.clone(),
+                source: gun.empty_sound().unwrap_or(&self.gun_empty).clone(),
                 settings: PlaybackSettings::DESPAWN
                     .with_volume(Volume::new_relative(volume))
                     .with_speed(speed),
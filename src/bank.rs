@@ -0,0 +1,264 @@
+use bevy::{prelude::*, sprite::Anchor};
+
+use crate::{
+    clock::{Phone, MINUTES_PER_DAY},
+    money::{
+        DebtEvent, DebtKind, Debts, Money, MoneyLocale, PayDebtCommand, Wallet,
+        WalletNotificationSource,
+    },
+    notifications::{Notification, NotificationPriority, PostNotification},
+    phone::{phone_app_open, AddPhoneApp, PhoneApp, PhoneAppInput, PhoneApps, PhoneInput},
+    GameState,
+};
+
+pub struct BankPlugin;
+
+impl Plugin for BankPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_phone_app::<BankApp>()
+            .init_resource::<BankSelection>()
+            .insert_resource(WalletNotificationSource(BankApp::NAME))
+            .add_systems(
+                Update,
+                (
+                    spawn_bank_ui,
+                    bank_input,
+                    notify_debt_events,
+                    update_bank_ui.run_if(phone_app_open::<BankApp>),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::RunAndGun)),
+            );
+    }
+}
+
+#[derive(Component, Default)]
+pub struct BankApp;
+
+impl PhoneApp for BankApp {
+    const NAME: &'static str = "Bank";
+    const ICON_COLOR: Color = Color::rgb(0.15, 0.45, 0.25);
+}
+
+const DEBTS: [DebtKind; 3] = [DebtKind::Medical, DebtKind::Rent, DebtKind::Utilities];
+const LEDGER_LINES: usize = 6;
+
+/// How much to pay toward the selected debt
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaymentAmount {
+    MinimumDue,
+    Fixed(Money),
+    Everything,
+}
+
+impl PaymentAmount {
+    const OPTIONS: [PaymentAmount; 5] = [
+        PaymentAmount::MinimumDue,
+        PaymentAmount::Fixed(Money::from_dollars(10)),
+        PaymentAmount::Fixed(Money::from_dollars(50)),
+        PaymentAmount::Fixed(Money::from_dollars(100)),
+        PaymentAmount::Everything,
+    ];
+
+    /// What this option would actually move out of the wallet
+    pub fn resolve(&self, wallet: &Wallet, debts: &Debts, debt: DebtKind) -> Money {
+        let owed = debts.account(debt).principal();
+        let amount = match self {
+            PaymentAmount::MinimumDue => debts.account(debt).minimum_due(),
+            PaymentAmount::Fixed(amount) => *amount,
+            PaymentAmount::Everything => wallet.funds(),
+        };
+        amount.min(owed).min(wallet.funds())
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct BankSelection {
+    debt: usize,
+    amount: usize,
+}
+
+impl BankSelection {
+    pub fn debt(&self) -> DebtKind {
+        DEBTS[self.debt]
+    }
+
+    pub fn amount(&self) -> PaymentAmount {
+        PaymentAmount::OPTIONS[self.amount]
+    }
+}
+
+#[derive(Component)]
+struct BankLedgerText;
+
+#[derive(Component)]
+struct BankDebtsText;
+
+fn spawn_bank_ui(mut commands: Commands, root_query: Query<Entity, Added<BankApp>>) {
+    let style = TextStyle {
+        font_size: 18.0,
+        ..default()
+    };
+
+    for root in &root_query {
+        commands.entity(root).with_children(|parent| {
+            parent
+                .spawn(Text2dBundle {
+                    text: Text::from_section("", style.clone()),
+                    text_anchor: Anchor::TopLeft,
+                    transform: Transform::from_xyz(-245.0, 85.0, 1.0),
+                    ..default()
+                })
+                .insert(BankLedgerText);
+            parent
+                .spawn(Text2dBundle {
+                    text: Text::from_section("", style.clone()),
+                    text_anchor: Anchor::TopLeft,
+                    transform: Transform::from_xyz(5.0, 85.0, 1.0),
+                    ..default()
+                })
+                .insert(BankDebtsText);
+        });
+    }
+}
+
+fn bank_input(
+    mut commands: Commands,
+    mut app_inputs: EventReader<PhoneAppInput>,
+    apps: Res<PhoneApps>,
+    mut selection: ResMut<BankSelection>,
+    wallet: Res<Wallet>,
+    debts: Res<Debts>,
+) {
+    for input in apps.inputs_for::<BankApp>(&mut app_inputs) {
+        match input {
+            PhoneInput::Up => selection.debt = selection.debt.saturating_sub(1),
+            PhoneInput::Down => selection.debt = (selection.debt + 1).min(DEBTS.len() - 1),
+            PhoneInput::Left => selection.amount = selection.amount.saturating_sub(1),
+            PhoneInput::Right => {
+                selection.amount = (selection.amount + 1).min(PaymentAmount::OPTIONS.len() - 1)
+            }
+            PhoneInput::Select => {
+                let debt = selection.debt();
+                let amount = selection.amount().resolve(&wallet, &debts, debt);
+                if amount.is_positive() {
+                    commands.add(PayDebtCommand { debt, amount });
+                    continue;
+                }
+
+                let reason = if debts.account(debt).is_paid_off() {
+                    format!("Your {} debt is already paid off", debt)
+                } else if !wallet.funds().is_positive() {
+                    "Your wallet is empty".to_string()
+                } else {
+                    format!("Nothing is due on your {} debt yet", debt)
+                };
+                commands.add(PostNotification(
+                    Notification::new("Payment not sent", reason).from_app::<BankApp>(),
+                ));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn notify_debt_events(
+    mut commands: Commands,
+    mut debt_events: EventReader<DebtEvent>,
+    locale: Res<MoneyLocale>,
+) {
+    for event in debt_events.iter() {
+        let notification = match event {
+            DebtEvent::Billed { debt, amount } => Notification::new(
+                format!("{} bill", debt),
+                format!("{} added to your balance", amount.localized(&locale)),
+            ),
+            DebtEvent::MissedPayment { debt, late_fee, .. } => Notification::new(
+                format!("Missed {} payment", debt),
+                format!("Late fee {}", late_fee.localized(&locale)),
+            )
+            .with_priority(NotificationPriority::High),
+            DebtEvent::InterestCharged { debt, amount } => Notification::new(
+                format!("{} interest", debt),
+                format!("{} charged", amount.localized(&locale)),
+            )
+            .with_priority(NotificationPriority::Low)
+            .with_tag(format!("interest {}", debt)),
+            DebtEvent::PaidOff { debt } => {
+                Notification::new(format!("{} paid off", debt), "Nothing left to pay")
+            }
+            DebtEvent::Payment { .. } => continue,
+        };
+        commands.add(PostNotification(notification.from_app::<BankApp>()));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_bank_ui(
+    phone: Res<Phone>,
+    wallet: Res<Wallet>,
+    debts: Res<Debts>,
+    locale: Res<MoneyLocale>,
+    selection: Res<BankSelection>,
+    mut ledger_query: Query<&mut Text, (With<BankLedgerText>, Without<BankDebtsText>)>,
+    mut debts_query: Query<&mut Text, (With<BankDebtsText>, Without<BankLedgerText>)>,
+    added_query: Query<(), Added<BankLedgerText>>,
+) {
+    if !(wallet.is_changed()
+        || debts.is_changed()
+        || selection.is_changed()
+        || phone.is_changed()
+        || !added_query.is_empty())
+    {
+        return;
+    }
+
+    if let Ok(mut text) = ledger_query.get_single_mut() {
+        let mut lines = vec![format!("Balance {}", wallet.funds().localized(&locale))];
+        lines.extend(wallet.ledger().latest(LEDGER_LINES).map(|entry| {
+            format!(
+                "{} {} {}",
+                entry.date,
+                entry.memo,
+                entry.amount.localized(&locale)
+            )
+        }));
+        text.sections[0].value = lines.join("\n");
+    }
+
+    if let Ok(mut text) = debts_query.get_single_mut() {
+        let mut lines = Vec::new();
+        for (index, debt) in DEBTS.into_iter().enumerate() {
+            let account = debts.account(debt);
+            let cursor = if index == selection.debt { ">" } else { " " };
+            let due = phone.date.next_weekly(account.due_day(), 0, 0);
+            let days_left = phone.date.minutes_until(&due) / MINUTES_PER_DAY as i64;
+            lines.push(format!(
+                "{} {} {}",
+                cursor,
+                debt,
+                account.principal().localized(&locale)
+            ));
+            lines.push(format!(
+                "   min {} due {} ({}d)",
+                account.minimum_due().localized(&locale),
+                account.due_day(),
+                days_left
+            ));
+        }
+
+        let debt = selection.debt();
+        let amount = selection.amount().resolve(&wallet, &debts, debt);
+        let label = match selection.amount() {
+            PaymentAmount::MinimumDue => "Minimum",
+            PaymentAmount::Fixed(_) => "Pay",
+            PaymentAmount::Everything => "Max",
+        };
+        lines.push(format!(
+            "< {} {} > [Select]",
+            label,
+            amount.localized(&locale)
+        ));
+        text.sections[0].value = lines.join("\n");
+    }
+}
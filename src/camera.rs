@@ -15,7 +15,7 @@ impl Plugin for PlayerCameraPlugin {
             .add_systems(
                 Update,
                 (
-                    read_rotation_inputs_primary,
+                    read_rotation_inputs_primary.run_if(crate::phone::phone_lowered),
                     switch_camera_perspective,
                     target_player,
                     position_and_rotate_camera,
@@ -247,6 +247,7 @@ pub enum AimMode {
 
 fn move_first_person_gun(
     time: Res<Time>,
+    belt: Res<crate::inventory::Belt>,
     camera_query: Query<&Transform, With<PrimaryCamera>>,
     mut gun_query: Query<(&mut Transform, &FirstPersonGun), Without<PrimaryCamera>>,
 ) {
@@ -274,6 +275,10 @@ fn move_first_person_gun(
             }
         };
 
+        // Dip out of view while switching weapons
+        let desired_translation =
+            desired_translation - camera_transform.up() * belt.holster_amount() * 0.6;
+
         gun_transform.translation = gun_transform
             .translation
             .lerp(desired_translation, 50.0 * time.delta_seconds());
@@ -321,7 +326,7 @@ fn update_camera_focus(
     }
 }
 
-fn spawn_camera(mut commands: Commands, assets: Res<AssetServer>) {
+fn spawn_camera(mut commands: Commands) {
     commands
         .spawn(Camera3dBundle {
             transform: Transform::from_xyz(0.0, 5.0, -5.0).looking_at(Vec3::ZERO, Vec3::Y),
@@ -331,14 +336,13 @@ fn spawn_camera(mut commands: Commands, assets: Res<AssetServer>) {
             },
             ..default()
         })
+        .insert(FogSettings::default())
         .insert(PrimaryCamera::default())
         .insert(RenderLayers::layer(0));
 
+    // The model comes from whichever gun is on the belt
     commands
-        .spawn(SceneBundle {
-            scene: assets.load("gun.glb#Scene0"),
-            ..default()
-        })
+        .spawn(SceneBundle::default())
         .insert(FirstPersonGun::default())
         .insert(Name::new("Gun"));
 }
@@ -1,4 +1,5 @@
 use crate::hud::PhoneDisplay;
+use crate::scheduler::{ScheduleFired, Scheduler};
 use bevy::prelude::*;
 use std::ops::{Add, AddAssign};
 
@@ -6,7 +7,10 @@ pub struct ClockPlugin;
 
 impl Plugin for ClockPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(crate::GameState::RunAndGun), spawn_clock)
+        app.add_event::<HourPassed>()
+            .register_type::<TimeScale>()
+            .insert_resource(TimeScale::default())
+            .add_systems(OnEnter(crate::GameState::RunAndGun), spawn_clock)
             .add_systems(
                 Update,
                 (advance_time, display_time).run_if(in_state(crate::GameState::RunAndGun)),
@@ -14,7 +18,7 @@ impl Plugin for ClockPlugin {
     }
 }
 
-#[derive(Copy, Clone, Default, PartialEq, PartialOrd)]
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, PartialOrd, Hash, Reflect)]
 pub enum Day {
     #[default]
     Sunday,
@@ -76,57 +80,63 @@ impl AddAssign<u8> for Day {
     }
 }
 
-#[derive(Copy, Clone, Default, PartialEq, PartialOrd)]
-pub enum Minute {
-    #[default]
-    Flat,
-    Quarter,
-    Half,
-    FortyFive,
+pub const MINUTES_PER_HOUR: u32 = 60;
+/// When every run starts
+pub const START_DATE: Date = Date::new(Day::Monday, 18, 30);
+pub const MINUTES_PER_DAY: u32 = 24 * MINUTES_PER_HOUR;
+
+/// Fastest time can be fast-forwarded
+const MAX_MULTIPLIER: f32 = 10_000.0;
+
+/// How fast in-game time runs compared to real time
+#[derive(Resource, Reflect, Clone, Debug)]
+#[reflect(Resource)]
+pub struct TimeScale {
+    /// Real seconds it takes for one in-game minute to pass at normal speed
+    pub real_seconds_per_minute: f32,
+    /// Fast-forward factor, 1.0 is normal speed
+    pub multiplier: f32,
+    pub paused: bool,
 }
 
-impl Minute {
-    fn index(&self) -> u8 {
-        *self as u8
+impl TimeScale {
+    pub fn minute_duration(&self) -> std::time::Duration {
+        std::time::Duration::from_secs_f32(self.real_seconds_per_minute.max(0.001))
     }
-}
 
-impl std::fmt::Display for Minute {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let string_representation = match self {
-            Self::Flat => "00",
-            Self::Quarter => "15",
-            Self::Half => "30",
-            Self::FortyFive => "45",
-        };
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
 
-        write!(f, "{}", string_representation)
+    pub fn resume(&mut self) {
+        self.paused = false;
     }
-}
 
-impl From<u8> for Minute {
-    fn from(value: u8) -> Self {
-        let index = value % 4;
-        match index {
-            0 => Self::Flat,
-            1 => Self::Quarter,
-            2 => Self::Half,
-            _ => Self::FortyFive,
+    pub fn fast_forward(&mut self, multiplier: f32) {
+        self.multiplier = multiplier.max(0.0);
+    }
+
+    /// `multiplier` kept to something time can actually run at, it can be set to anything from
+    /// the inspector
+    pub fn effective_multiplier(&self) -> f32 {
+        if self.multiplier.is_nan() {
+            return 0.0;
         }
+        self.multiplier.clamp(0.0, MAX_MULTIPLIER)
     }
-}
 
-impl Add<u8> for Minute {
-    type Output = Self;
-    fn add(self, rhs: u8) -> Self::Output {
-        let new_index = (self.index() + rhs) % 4;
-        new_index.into()
+    pub fn normal_speed(&mut self) {
+        self.multiplier = 1.0;
     }
 }
 
-impl AddAssign<u8> for Minute {
-    fn add_assign(&mut self, rhs: u8) {
-        *self = *self + rhs;
+impl Default for TimeScale {
+    fn default() -> Self {
+        TimeScale {
+            real_seconds_per_minute: 4.0,
+            multiplier: 1.0,
+            paused: false,
+        }
     }
 }
 
@@ -137,65 +147,195 @@ pub struct Phone {
 }
 
 impl Phone {
-    pub fn tick(&mut self, delta: std::time::Duration) {
-        self.timer.tick(delta);
-        if self.timer.just_finished() {
-            self.date.advance();
+    /// Returns true when at least one in-game minute passed on this tick
+    pub fn tick(&mut self, delta: std::time::Duration, time_scale: &TimeScale) -> bool {
+        if time_scale.paused {
+            return false;
+        }
+
+        if self.timer.duration() != time_scale.minute_duration() {
+            self.timer.set_duration(time_scale.minute_duration());
         }
+
+        self.timer
+            .tick(delta.mul_f32(time_scale.effective_multiplier()));
+        let minutes = self.timer.times_finished_this_tick();
+        if minutes > 0 {
+            self.date = self.date.add_minutes(minutes);
+            return true;
+        }
+        false
     }
 }
 
-#[derive(Default, Clone, Copy)]
+/// Sent every time the clock reaches the top of an hour, carrying the new date
+#[derive(Event, Clone, Copy)]
+pub struct HourPassed(pub Date);
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Reflect)]
 pub struct Date {
-    pub minute: Minute,
+    pub minute: u8,
     pub hour: u8,
     pub day: Day,
+    /// Days elapsed since the game started
+    pub day_number: u32,
 }
 
 impl Date {
-    pub fn new(day: Day, hour: u8, minute: Minute) -> Self {
-        Date { day, hour, minute }
+    /// A date in the first week, so `day_number` lines up with `day`
+    pub const fn new(day: Day, hour: u8, minute: u8) -> Self {
+        Date {
+            day,
+            hour,
+            minute,
+            day_number: day as u32,
+        }
+    }
+
+    pub fn week(&self) -> u32 {
+        self.day_number / 7
     }
 
+    pub fn minute_of_day(&self) -> u32 {
+        self.hour as u32 * MINUTES_PER_HOUR + self.minute as u32
+    }
+
+    /// Minutes elapsed since midnight of the first day
+    pub fn total_minutes(&self) -> u64 {
+        self.day_number as u64 * MINUTES_PER_DAY as u64 + self.minute_of_day() as u64
+    }
+
+    /// Negative when `other` is earlier than this date
+    pub fn minutes_until(&self, other: &Date) -> i64 {
+        other.total_minutes() as i64 - self.total_minutes() as i64
+    }
+
+    pub fn add_minutes(&self, minutes: u32) -> Date {
+        let minute_of_day = self.minute_of_day() + minutes;
+        let days = minute_of_day / MINUTES_PER_DAY;
+        let minute_of_day = minute_of_day % MINUTES_PER_DAY;
+
+        Date {
+            minute: (minute_of_day % MINUTES_PER_HOUR) as u8,
+            hour: (minute_of_day / MINUTES_PER_HOUR) as u8,
+            day: self.day + (days % 7) as u8,
+            day_number: self.day_number + days,
+        }
+    }
+
+    /// Moves forward one minute, returning true when that rolls over to a new day
     pub fn advance(&mut self) -> bool {
-        let old_date = self.clone();
-        self.minute += 1;
-        if self.minute.index() < old_date.minute.index() {
-            self.hour = (self.hour + 1) % 24;
-            if self.hour < old_date.hour {
-                self.day += 1;
-                return true;
-            }
+        let old_date = *self;
+        *self = self.add_minutes(1);
+        self.day_number != old_date.day_number
+    }
+
+    /// The first time after this date that the clock reads `hour:minute`
+    pub fn next_daily(&self, hour: u8, minute: u8) -> Date {
+        let target = hour as u32 * MINUTES_PER_HOUR + minute as u32;
+        let mut minutes = (target + MINUTES_PER_DAY - self.minute_of_day()) % MINUTES_PER_DAY;
+        if minutes == 0 {
+            minutes = MINUTES_PER_DAY;
         }
-        false
+        self.add_minutes(minutes)
+    }
+
+    /// The first time after this date that the clock reads `day hour:minute`
+    pub fn next_weekly(&self, day: Day, hour: u8, minute: u8) -> Date {
+        let days_ahead = (day.index() + 7 - self.day.index()) % 7;
+        let target =
+            days_ahead as u32 * MINUTES_PER_DAY + hour as u32 * MINUTES_PER_HOUR + minute as u32;
+        let minutes = if target > self.minute_of_day() {
+            target - self.minute_of_day()
+        } else {
+            target + 7 * MINUTES_PER_DAY - self.minute_of_day()
+        };
+        self.add_minutes(minutes)
+    }
+
+    /// Every top of the hour after `from`, up to and including `to`
+    pub fn hours_between(from: Date, to: Date) -> Vec<Date> {
+        let mut hours = Vec::new();
+        let mut hour = from.add_minutes(MINUTES_PER_HOUR - from.minute as u32);
+        while hour <= to {
+            hours.push(hour);
+            hour = hour.add_minutes(MINUTES_PER_HOUR);
+        }
+        hours
+    }
+
+    fn sort_key(&self) -> (u32, u8, u8) {
+        (self.day_number, self.hour, self.minute)
+    }
+}
+
+impl Ord for Date {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.sort_key().cmp(&other.sort_key())
+    }
+}
+
+impl PartialOrd for Date {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
     }
 }
 
 impl std::fmt::Display for Date {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{} {:0>2}:{}",
-            self.day.to_string(),
-            self.hour,
-            self.minute
-        )
+        write!(f, "{} {:0>2}:{:0>2}", self.day, self.hour, self.minute)
     }
 }
 
-fn spawn_clock(mut commands: Commands) {
+pub fn spawn_clock(
+    mut commands: Commands,
+    time_scale: Res<TimeScale>,
+    mut scheduler: ResMut<Scheduler>,
+) {
     let phone = Phone {
-        date: Date::new(Day::Monday, 18, Minute::Half),
-        timer: Timer::from_seconds(60.0, TimerMode::Repeating),
+        date: START_DATE,
+        timer: Timer::new(time_scale.minute_duration(), TimerMode::Repeating),
     };
 
     println!("{}", phone.date.to_string());
 
+    scheduler.set_now(phone.date);
     commands.insert_resource(phone);
 }
 
-pub fn advance_time(time: Res<Time>, mut phone: ResMut<Phone>) {
-    phone.tick(time.delta());
+pub fn advance_time(
+    mut commands: Commands,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    mut phone: ResMut<Phone>,
+    mut scheduler: ResMut<Scheduler>,
+    mut hour_events: EventWriter<HourPassed>,
+    mut fired_events: EventWriter<ScheduleFired>,
+) {
+    let previous = phone.date;
+    if phone.tick(time.delta(), &time_scale) {
+        pass_time(
+            previous,
+            phone.date,
+            &mut scheduler,
+            &mut commands,
+            &mut hour_events,
+            &mut fired_events,
+        );
+    }
+}
+
+/// Announces every hour and scheduled entry between `from` and `to`
+pub fn pass_time(
+    from: Date,
+    to: Date,
+    scheduler: &mut Scheduler,
+    commands: &mut Commands,
+    hour_events: &mut EventWriter<HourPassed>,
+    fired_events: &mut EventWriter<ScheduleFired>,
+) {
+    hour_events.send_batch(Date::hours_between(from, to).into_iter().map(HourPassed));
+    scheduler.fire_until(to, commands, fired_events);
 }
 
 pub fn display_time(
@@ -0,0 +1,252 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    core_pipeline::clear_color::ClearColorConfig,
+    pbr::NotShadowCaster,
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+use crate::{camera::PrimaryCamera, clock::Phone, GameState};
+
+pub struct DaylightPlugin;
+
+impl Plugin for DaylightPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<DaylightCurves>()
+            .init_asset_loader::<DaylightCurvesLoader>()
+            .add_systems(OnEnter(GameState::RunAndGun), spawn_sun_and_sky)
+            .add_systems(
+                Update,
+                (update_daylight, follow_camera_with_sky).run_if(in_state(GameState::RunAndGun)),
+            );
+    }
+}
+
+/// Lighting at a single time of day, `hour` being fractional hours since midnight
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct DaylightKey {
+    pub hour: f32,
+    pub sun_color: [f32; 3],
+    pub sun_illuminance: f32,
+    pub moon_color: [f32; 3],
+    pub moon_illuminance: f32,
+    pub ambient_color: [f32; 3],
+    pub ambient_brightness: f32,
+    pub sky_color: [f32; 3],
+    pub night_sky: f32,
+    pub fog_color: [f32; 3],
+    pub fog_start: f32,
+    pub fog_end: f32,
+}
+
+impl DaylightKey {
+    fn lerp(&self, other: &DaylightKey, t: f32) -> DaylightKey {
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+        let mix_color =
+            |a: [f32; 3], b: [f32; 3]| [mix(a[0], b[0]), mix(a[1], b[1]), mix(a[2], b[2])];
+
+        DaylightKey {
+            hour: mix(self.hour, other.hour),
+            sun_color: mix_color(self.sun_color, other.sun_color),
+            sun_illuminance: mix(self.sun_illuminance, other.sun_illuminance),
+            moon_color: mix_color(self.moon_color, other.moon_color),
+            moon_illuminance: mix(self.moon_illuminance, other.moon_illuminance),
+            ambient_color: mix_color(self.ambient_color, other.ambient_color),
+            ambient_brightness: mix(self.ambient_brightness, other.ambient_brightness),
+            sky_color: mix_color(self.sky_color, other.sky_color),
+            night_sky: mix(self.night_sky, other.night_sky),
+            fog_color: mix_color(self.fog_color, other.fog_color),
+            fog_start: mix(self.fog_start, other.fog_start),
+            fog_end: mix(self.fog_end, other.fog_end),
+        }
+    }
+}
+
+fn to_color(rgb: [f32; 3]) -> Color {
+    Color::rgb(rgb[0], rgb[1], rgb[2])
+}
+
+/// Keyframes over a day, loaded from a `.daylight.ron` file
+#[derive(Deserialize, TypeUuid, TypePath, Debug, Clone)]
+#[uuid = "a55a5982-b52e-455f-a04b-ba37bcc2a2b2"]
+pub struct DaylightCurves {
+    keys: Vec<DaylightKey>,
+}
+
+impl DaylightCurves {
+    /// Interpolates between the keys around `hour`, wrapping past midnight
+    pub fn sample(&self, hour: f32) -> Option<DaylightKey> {
+        let first = self.keys.first()?;
+        let last = self.keys.last()?;
+
+        let (from, to, from_hour, to_hour) = match self.keys.iter().position(|key| key.hour > hour)
+        {
+            Some(0) => (last, first, last.hour - 24.0, first.hour),
+            Some(index) => (
+                &self.keys[index - 1],
+                &self.keys[index],
+                self.keys[index - 1].hour,
+                self.keys[index].hour,
+            ),
+            None => (last, first, last.hour, first.hour + 24.0),
+        };
+
+        let span = to_hour - from_hour;
+        let t = if span > 0.0 {
+            (hour - from_hour) / span
+        } else {
+            0.0
+        };
+        Some(from.lerp(to, t.clamp(0.0, 1.0)))
+    }
+}
+
+#[derive(Default)]
+pub struct DaylightCurvesLoader;
+
+impl AssetLoader for DaylightCurvesLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let mut curves = ron::de::from_bytes::<DaylightCurves>(bytes)?;
+            curves.keys.sort_by(|a, b| a.hour.total_cmp(&b.hour));
+            load_context.set_default_asset(LoadedAsset::new(curves));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["daylight.ron"]
+    }
+}
+
+#[derive(Component)]
+pub struct Sun;
+
+#[derive(Component)]
+pub struct Moon;
+
+#[derive(Component)]
+pub struct SkySphere;
+
+fn spawn_sun_and_sky(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    assets: Res<AssetServer>,
+) {
+    commands
+        .spawn(DirectionalLightBundle {
+            directional_light: DirectionalLight {
+                shadows_enabled: true,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(Sun)
+        .insert(Name::new("Sun"));
+
+    commands
+        .spawn(DirectionalLightBundle::default())
+        .insert(Moon)
+        .insert(Name::new("Moon"));
+
+    commands
+        .spawn(PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::UVSphere {
+                radius: 500.0,
+                sectors: 32,
+                stacks: 16,
+            })),
+            material: materials.add(StandardMaterial {
+                base_color: Color::rgba(1.0, 1.0, 1.0, 0.0),
+                base_color_texture: Some(assets.load("skyboxes/nightsky.jpeg")),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                fog_enabled: false,
+                cull_mode: None,
+                ..default()
+            }),
+            ..default()
+        })
+        .insert(NotShadowCaster)
+        .insert(SkySphere)
+        .insert(Name::new("Sky"));
+}
+
+fn follow_camera_with_sky(
+    camera_query: Query<&Transform, (With<PrimaryCamera>, Without<SkySphere>)>,
+    mut sky_query: Query<&mut Transform, With<SkySphere>>,
+) {
+    if let Ok(camera_transform) = camera_query.get_single() {
+        for mut transform in &mut sky_query {
+            transform.translation = camera_transform.translation;
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn update_daylight(
+    phone: Res<Phone>,
+    asset_cache: Res<crate::AssetCache>,
+    curves: Res<Assets<DaylightCurves>>,
+    mut ambient_light: ResMut<AmbientLight>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut sun_query: Query<(&mut DirectionalLight, &mut Transform), (With<Sun>, Without<Moon>)>,
+    mut moon_query: Query<(&mut DirectionalLight, &mut Transform), (With<Moon>, Without<Sun>)>,
+    mut camera_query: Query<(&mut Camera3d, &mut FogSettings), With<PrimaryCamera>>,
+    sky_query: Query<&Handle<StandardMaterial>, With<SkySphere>>,
+) {
+    let hour = phone.date.minute_of_day() as f32 / 60.0;
+    let Some(key) = curves
+        .get(&asset_cache.daylight)
+        .and_then(|curves| curves.sample(hour))
+    else {
+        return;
+    };
+
+    // The sun rises at 06:00, peaks at noon and sets at 18:00; the moon sits opposite
+    let angle = (hour - 6.0) / 24.0 * std::f32::consts::TAU;
+    let sun_position = Vec3::new(angle.cos(), angle.sin(), 0.3).normalize();
+    let elevation = sun_position.y;
+
+    if let Ok((mut light, mut transform)) = sun_query.get_single_mut() {
+        light.color = to_color(key.sun_color);
+        light.illuminance = key.sun_illuminance * (elevation * 4.0).clamp(0.0, 1.0);
+        *transform = Transform::from_translation(sun_position).looking_at(Vec3::ZERO, Vec3::Y);
+    }
+
+    if let Ok((mut light, mut transform)) = moon_query.get_single_mut() {
+        light.color = to_color(key.moon_color);
+        light.illuminance = key.moon_illuminance * (-elevation * 4.0).clamp(0.0, 1.0);
+        *transform = Transform::from_translation(-sun_position).looking_at(Vec3::ZERO, Vec3::Y);
+    }
+
+    ambient_light.color = to_color(key.ambient_color);
+    ambient_light.brightness = key.ambient_brightness;
+
+    for (mut camera_3d, mut fog) in &mut camera_query {
+        camera_3d.clear_color = ClearColorConfig::Custom(to_color(key.sky_color));
+        fog.color = to_color(key.fog_color);
+        fog.directional_light_color = to_color(key.sun_color).with_a(0.3);
+        fog.falloff = FogFalloff::Linear {
+            start: key.fog_start,
+            end: key.fog_end,
+        };
+    }
+
+    let night_sky = key.night_sky.clamp(0.0, 1.0);
+    for handle in &sky_query {
+        let needs_update = materials
+            .get(handle)
+            .is_some_and(|material| (material.base_color.a() - night_sky).abs() > 0.001);
+        if let (true, Some(material)) = (needs_update, materials.get_mut(handle)) {
+            material.base_color.set_a(night_sky);
+        }
+    }
+}
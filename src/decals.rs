@@ -0,0 +1,204 @@
+use std::f32::consts::TAU;
+
+use bevy::{ecs::system::Command, pbr::NotShadowCaster, prelude::*};
+use rand::{thread_rng, Rng};
+
+use crate::{
+    shooting::ShotHit,
+    surface::{surface_of, SurfaceKind, SurfaceQuery},
+    AssetCache, GameState,
+};
+
+pub struct DecalPlugin;
+
+impl Plugin for DecalPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DecalPool>().add_systems(
+            Update,
+            (place_decals, fade_decals)
+                .chain()
+                .run_if(in_state(GameState::RunAndGun)),
+        );
+    }
+}
+
+/// Decals that can exist at once, past this the oldest one is moved to the new hit
+const MAX_DECALS: usize = 128;
+const DECAL_SIZE: f32 = 0.12;
+/// Seconds a decal stays fully visible before it starts to fade
+const DECAL_LIFETIME: f32 = 30.0;
+const DECAL_FADE_TIME: f32 = 5.0;
+/// Lifts decals off the surface so they don't flicker through it
+const DECAL_OFFSET: f32 = 0.002;
+
+/// A bullet hole left where a shot hit, owned by the `DecalPool`
+#[derive(Component, Default)]
+pub struct Decal {
+    age: f32,
+}
+
+/// Ring buffer of every decal entity, so long fights don't keep piling up entities
+#[derive(Resource)]
+struct DecalPool {
+    decals: Vec<Entity>,
+    /// The slot the next hit goes in
+    next: usize,
+    mesh: Handle<Mesh>,
+}
+
+impl FromWorld for DecalPool {
+    fn from_world(world: &mut World) -> Self {
+        let mesh = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Mesh::from(shape::Quad::new(Vec2::splat(DECAL_SIZE))));
+        DecalPool {
+            decals: Vec::with_capacity(MAX_DECALS),
+            next: 0,
+            mesh,
+        }
+    }
+}
+
+fn decal_texture(kind: SurfaceKind) -> &'static str {
+    match kind {
+        SurfaceKind::Default => "decals/default.png",
+        SurfaceKind::Wood => "decals/wood.png",
+        SurfaceKind::Metal => "decals/metal.png",
+        SurfaceKind::Concrete => "decals/concrete.png",
+        SurfaceKind::Glass => "decals/glass.png",
+        SurfaceKind::Flesh => "decals/flesh.png",
+    }
+}
+
+/// Sticks a decal to whatever was hit so it moves with it, or gets rid of the decal if that's
+/// already gone. Either can be despawned before this runs, reused decals along with whatever
+/// they were stuck to before
+struct AttachDecal {
+    decal: Entity,
+    target: Entity,
+    /// Relative to `target`
+    transform: Transform,
+}
+
+impl Command for AttachDecal {
+    fn apply(self, world: &mut World) {
+        if world.get_entity(self.target).is_none() {
+            if let Some(decal) = world.get_entity_mut(self.decal) {
+                decal.despawn_recursive();
+            }
+            return;
+        }
+        let Some(mut decal) = world.get_entity_mut(self.decal) else {
+            return;
+        };
+        decal
+            .insert((self.transform, Visibility::Inherited))
+            .set_parent(self.target);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn place_decals(
+    mut commands: Commands,
+    mut hit_events: EventReader<ShotHit>,
+    mut pool: ResMut<DecalPool>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_cache: Res<AssetCache>,
+    surface_query: SurfaceQuery,
+    target_query: Query<&GlobalTransform>,
+    mut decal_query: Query<(&mut Decal, &Handle<StandardMaterial>)>,
+) {
+    let mut rng = thread_rng();
+
+    for hit in hit_events.iter() {
+        let Ok(target_transform) = target_query.get(hit.entity) else {
+            continue;
+        };
+        let normal = hit.normal.try_normalize().unwrap_or(-hit.dir);
+        let rotation = Quat::from_rotation_arc(Vec3::Z, normal)
+            * Quat::from_rotation_z(rng.gen_range(0.0..TAU));
+        let transform = GlobalTransform::from(
+            Transform::from_translation(hit.point + normal * DECAL_OFFSET).with_rotation(rotation),
+        )
+        .reparented_to(target_transform);
+        let texture = asset_cache
+            .decals
+            .get(decal_texture(surface_of(hit.entity, &surface_query).kind))
+            .cloned();
+
+        let slot = pool.next;
+        pool.next = (pool.next + 1) % MAX_DECALS;
+
+        // Decals that went down with whatever they were on have to be replaced
+        let reused = pool
+            .decals
+            .get(slot)
+            .copied()
+            .filter(|decal| decal_query.contains(*decal));
+        let decal = match reused {
+            Some(decal) => {
+                let (mut state, material) = decal_query.get_mut(decal).unwrap();
+                state.age = 0.0;
+                if let Some(material) = materials.get_mut(material) {
+                    material.base_color = Color::WHITE;
+                    material.base_color_texture = texture;
+                }
+                decal
+            }
+            None => {
+                let material = materials.add(StandardMaterial {
+                    base_color_texture: texture,
+                    alpha_mode: AlphaMode::Blend,
+                    ..default()
+                });
+                let decal = commands
+                    .spawn((
+                        PbrBundle {
+                            mesh: pool.mesh.clone(),
+                            material,
+                            transform,
+                            ..default()
+                        },
+                        Decal::default(),
+                        NotShadowCaster,
+                        Name::new("Bullet Hole"),
+                    ))
+                    .id();
+                if slot < pool.decals.len() {
+                    pool.decals[slot] = decal;
+                } else {
+                    pool.decals.push(decal);
+                }
+                decal
+            }
+        };
+        commands.add(AttachDecal {
+            decal,
+            target: hit.entity,
+            transform,
+        });
+    }
+}
+
+fn fade_decals(
+    time: Res<Time>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut decal_query: Query<(&mut Decal, &Handle<StandardMaterial>, &mut Visibility)>,
+) {
+    for (mut decal, material, mut visibility) in &mut decal_query {
+        if decal.age > DECAL_LIFETIME + DECAL_FADE_TIME {
+            continue;
+        }
+        decal.age += time.delta_seconds();
+        if decal.age < DECAL_LIFETIME {
+            continue;
+        }
+
+        let alpha = 1.0 - (decal.age - DECAL_LIFETIME) / DECAL_FADE_TIME;
+        if alpha <= 0.0 {
+            *visibility = Visibility::Hidden;
+        } else if let Some(material) = materials.get_mut(material) {
+            material.base_color.set_a(alpha);
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{RigidBody, Velocity};
+
+use crate::{
+    clock::Phone,
+    hud::HealthDisplay,
+    money::{Money, TransactionCategory, Wallet},
+    notifications::{Notification, NotificationPriority, PostNotification},
+    player::Player,
+    shooting::ShotHit,
+    GameState,
+};
+
+pub struct HealthPlugin;
+
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Health>()
+            .register_type::<Armor>()
+            .register_type::<OnDeath>()
+            .add_event::<DamageEvent>()
+            .add_event::<Died>()
+            .add_systems(
+                Update,
+                (
+                    damage_from_shots,
+                    apply_damage,
+                    handle_deaths,
+                    revive_player,
+                    collect_loot,
+                    track_player_health,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::RunAndGun)),
+            );
+    }
+}
+
+pub const PLAYER_HEALTH: f32 = 100.0;
+const PLAYER_RESPAWN: Vec3 = Vec3::new(0.0, 10.0, 0.0);
+const LOOT_PICKUP_DISTANCE: f32 = 1.5;
+/// Shots do full damage up to this fraction of their range
+const FALLOFF_START: f32 = 0.5;
+/// What's left of a shot's damage at the very end of its range
+const FALLOFF_MINIMUM: f32 = 0.25;
+
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component, Default)]
+pub struct Health {
+    pub max: f32,
+    pub current: f32,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Health::new(100.0)
+    }
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Health { max, current: max }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.0
+    }
+
+    pub fn fraction(&self) -> f32 {
+        (self.current / self.max).clamp(0.0, 1.0)
+    }
+
+    pub fn damage(&mut self, amount: f32) {
+        self.current = (self.current - amount).max(0.0);
+    }
+
+    pub fn heal(&mut self, amount: f32) {
+        self.current = (self.current + amount).min(self.max);
+    }
+}
+
+/// What happens to an entity once its `Health` runs out
+#[derive(Component, Reflect, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component, Default)]
+pub enum OnDeath {
+    #[default]
+    Despawn,
+    /// Goes limp and gets knocked around by physics
+    Ragdoll,
+    Nothing,
+}
+
+/// Fraction of incoming damage soaked up, worn down by a shot's penetration
+#[derive(Component, Reflect, Default, Debug, Clone, Copy)]
+#[reflect(Component, Default)]
+pub struct Armor(pub f32);
+
+impl Armor {
+    pub fn reduce(&self, amount: f32, penetration: f32) -> f32 {
+        amount * (1.0 - (self.0 - penetration).clamp(0.0, 1.0))
+    }
+}
+
+/// Money dropped where this entity dies
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Loot(pub Money);
+
+#[derive(Component)]
+pub struct LootDrop(Money);
+
+/// Marks entities that have already died so they only die once
+#[derive(Component)]
+pub struct Dead;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DamageType {
+    Bullet,
+    Projectile,
+    Fall,
+    Other,
+}
+
+#[derive(Event, Clone, Debug)]
+pub struct DamageEvent {
+    pub target: Entity,
+    pub source: Option<Entity>,
+    pub amount: f32,
+    pub point: Vec3,
+    pub damage_type: DamageType,
+    pub penetration: f32,
+}
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct Died {
+    pub entity: Entity,
+    pub source: Option<Entity>,
+}
+
+/// Scales damage down past `FALLOFF_START` of the shot's range
+pub fn range_falloff(distance: f32, range: f32) -> f32 {
+    if range <= 0.0 {
+        return 1.0;
+    }
+    let t = ((distance / range - FALLOFF_START) / (1.0 - FALLOFF_START)).clamp(0.0, 1.0);
+    1.0 - t * (1.0 - FALLOFF_MINIMUM)
+}
+
+fn damage_from_shots(
+    mut hit_events: EventReader<ShotHit>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    for hit in hit_events.iter() {
+        damage_events.send(DamageEvent {
+            target: hit.entity,
+            source: Some(hit.shooter),
+            amount: hit.base_damage as f32 * range_falloff(hit.distance, hit.range),
+            point: hit.point,
+            damage_type: hit.damage_type,
+            penetration: hit.penetration,
+        });
+    }
+}
+
+fn apply_damage(
+    mut commands: Commands,
+    mut damage_events: EventReader<DamageEvent>,
+    mut died_events: EventWriter<Died>,
+    mut health_query: Query<(&mut Health, Option<&Armor>), Without<Dead>>,
+) {
+    for event in damage_events.iter() {
+        // `Dead` only lands once commands apply, so later hits this frame have to check health
+        let Ok((mut health, armor)) = health_query.get_mut(event.target) else {
+            continue;
+        };
+        if health.is_dead() {
+            continue;
+        }
+        let amount = armor.map_or(event.amount, |armor| {
+            armor.reduce(event.amount, event.penetration)
+        });
+        health.damage(amount);
+        if health.is_dead() {
+            commands.entity(event.target).insert(Dead);
+            died_events.send(Died {
+                entity: event.target,
+                source: event.source,
+            });
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn handle_deaths(
+    mut commands: Commands,
+    mut died_events: EventReader<Died>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    death_query: Query<(&GlobalTransform, Option<&OnDeath>, Option<&Loot>), Without<Player>>,
+) {
+    for died in died_events.iter() {
+        let Ok((transform, on_death, loot)) = death_query.get(died.entity) else {
+            continue;
+        };
+
+        if let Some(Loot(amount)) = loot {
+            commands.spawn((
+                PbrBundle {
+                    mesh: meshes.add(Mesh::from(shape::Cube { size: 0.25 })),
+                    material: materials.add(StandardMaterial {
+                        base_color: Color::GOLD,
+                        emissive: Color::GOLD * 0.5,
+                        ..default()
+                    }),
+                    transform: Transform::from_translation(transform.translation()),
+                    ..default()
+                },
+                LootDrop(*amount),
+                Name::new("Loot"),
+            ));
+        }
+
+        match on_death.copied().unwrap_or_default() {
+            OnDeath::Despawn => commands.entity(died.entity).despawn_recursive(),
+            OnDeath::Ragdoll => {
+                commands
+                    .entity(died.entity)
+                    .insert(RigidBody::Dynamic)
+                    .remove::<Health>();
+            }
+            OnDeath::Nothing => {}
+        }
+    }
+}
+
+/// The player wakes up back at the start instead of dying for good
+fn revive_player(
+    mut commands: Commands,
+    mut died_events: EventReader<Died>,
+    mut player_query: Query<(Entity, &mut Health, &mut Transform, &mut Velocity), With<Player>>,
+) {
+    let Ok((entity, mut health, mut transform, mut velocity)) = player_query.get_single_mut()
+    else {
+        return;
+    };
+
+    if died_events.iter().any(|died| died.entity == entity) {
+        health.current = health.max;
+        transform.translation = PLAYER_RESPAWN;
+        *velocity = Velocity::default();
+        commands.entity(entity).remove::<Dead>();
+        commands.add(PostNotification(
+            Notification::new("You blacked out", "Woke up back where you started")
+                .with_icon(Color::RED)
+                .with_priority(NotificationPriority::High),
+        ));
+    }
+}
+
+fn collect_loot(
+    mut commands: Commands,
+    mut wallet: ResMut<Wallet>,
+    phone: Res<Phone>,
+    player_query: Query<&Transform, With<Player>>,
+    loot_query: Query<(Entity, &LootDrop, &Transform), Without<Player>>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+
+    for (entity, loot, transform) in &loot_query {
+        if transform.translation.distance(player_transform.translation) < LOOT_PICKUP_DISTANCE {
+            commands.add(wallet.credit(loot.0, TransactionCategory::Income, phone.date, "Loot"));
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn track_player_health(
+    player_query: Query<&Health, (With<Player>, Changed<Health>)>,
+    mut display_query: Query<&mut Text, With<HealthDisplay>>,
+) {
+    if let Ok(health) = player_query.get_single() {
+        for mut text in &mut display_query {
+            text.sections[0].value = format!("{:.0}", health.current);
+        }
+    }
+}
@@ -17,9 +17,15 @@ pub struct DebtDisplay;
 #[derive(Component)]
 pub struct AmmoDisplay;
 
+#[derive(Component)]
+pub struct FireModeDisplay;
+
 #[derive(Component)]
 pub struct WalletDisplay;
 
+#[derive(Component)]
+pub struct HealthDisplay;
+
 #[derive(Component)]
 pub struct PhoneDisplay;
 
@@ -73,6 +79,13 @@ fn spawn_hud(mut commands: Commands) {
                             ..default()
                         },
                     ),
+                    TextSection::new(
+                        " Standard",
+                        TextStyle {
+                            font_size: 24.0,
+                            ..default()
+                        },
+                    ),
                 ],
                 alignment: TextAlignment::Center,
                 ..default()
@@ -85,6 +98,44 @@ fn spawn_hud(mut commands: Commands) {
             ..default()
         })
         .insert(AmmoDisplay);
+    commands
+        .spawn(TextBundle {
+            text: Text::from_section(
+                "Auto",
+                TextStyle {
+                    font_size: 24.0,
+                    color: Color::rgb(0.8, 0.8, 0.8),
+                    ..default()
+                },
+            ),
+            style: Style {
+                bottom: Val::Px(56.0),
+                left: Val::Px(4.0),
+                position_type: bevy::ui::PositionType::Absolute,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(FireModeDisplay);
+    commands
+        .spawn(TextBundle {
+            text: Text::from_section(
+                "100",
+                TextStyle {
+                    font_size: 48.0,
+                    color: Color::rgb(0.9, 0.3, 0.3),
+                    ..default()
+                },
+            ),
+            style: Style {
+                bottom: Val::Percent(0.0),
+                right: Val::Px(10.0),
+                position_type: bevy::ui::PositionType::Absolute,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(HealthDisplay);
 
     commands.spawn(Camera3dBundle {
         camera_3d: Camera3d {
@@ -12,6 +12,23 @@ pub enum PlayerAction {
     Shoot,
     AimDownSights,
     Reload,
+    NextWeapon,
+    PreviousWeapon,
+    SelectWeapon1,
+    SelectWeapon2,
+    SelectWeapon3,
+    SelectWeapon4,
+    FireSelect,
+    CycleAmmo,
+    Interact,
+    TogglePhone,
+    PhoneUp,
+    PhoneDown,
+    PhoneLeft,
+    PhoneRight,
+    PhoneSelect,
+    PhoneBack,
+    PhoneHome,
 }
 
 #[derive(Bundle)]
@@ -28,8 +45,34 @@ impl InputListenerBundle {
             (KeyCode::Q, SwitchPerspective),
             (KeyCode::ShiftLeft, AimDownSights),
             (KeyCode::R, Reload),
+            (KeyCode::Key1, SelectWeapon1),
+            (KeyCode::Key2, SelectWeapon2),
+            (KeyCode::Key3, SelectWeapon3),
+            (KeyCode::Key4, SelectWeapon4),
+            (KeyCode::B, FireSelect),
+            (KeyCode::T, CycleAmmo),
+            (KeyCode::E, Interact),
+            (KeyCode::Tab, TogglePhone),
+            (KeyCode::Up, PhoneUp),
+            (KeyCode::Down, PhoneDown),
+            (KeyCode::Left, PhoneLeft),
+            (KeyCode::Right, PhoneRight),
+            (KeyCode::Return, PhoneSelect),
+            (KeyCode::Back, PhoneBack),
+            (KeyCode::Home, PhoneHome),
         ])
         .insert(MouseButton::Left, Shoot)
+        .insert(MouseButton::Left, PhoneSelect)
+        .insert(MouseButton::Right, PhoneBack)
+        .insert(GamepadButtonType::South, PhoneSelect)
+        .insert(GamepadButtonType::East, PhoneBack)
+        .insert(GamepadButtonType::Select, TogglePhone)
+        .insert(MouseWheelDirection::Up, NextWeapon)
+        .insert(MouseWheelDirection::Down, PreviousWeapon)
+        .insert(GamepadButtonType::DPadRight, NextWeapon)
+        .insert(GamepadButtonType::DPadLeft, PreviousWeapon)
+        .insert(GamepadButtonType::DPadUp, FireSelect)
+        .insert(GamepadButtonType::DPadDown, CycleAmmo)
         .insert_multiple([
             (DualAxis::mouse_motion(), Pan),
             (DualAxis::right_stick(), PanGamepad),
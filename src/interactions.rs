@@ -1,7 +1,14 @@
-use crate::inventory::Belt;
+use crate::{
+    ammo::{UseVendor, VendorState},
+    input::PlayerAction,
+    inventory::Belt,
+    sleep::SleepEvent,
+    workbench::{TakePart, UseWorkbench, WorkbenchState},
+};
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 use bevy_vector_shapes::prelude::*;
+use leafwing_input_manager::prelude::*;
 
 #[derive(Component)]
 pub struct Interactable(pub InteractionType);
@@ -9,6 +16,10 @@ pub struct Interactable(pub InteractionType);
 pub enum InteractionType {
     Talk,
     Pickup,
+    Sleep,
+    Workbench,
+    TakePart,
+    Vendor,
 }
 
 #[derive(Event)]
@@ -26,6 +37,18 @@ impl std::fmt::Display for InteractionType {
             InteractionType::Pickup => {
                 write!(f, "Pick-up")
             }
+            InteractionType::Sleep => {
+                write!(f, "Sleep")
+            }
+            InteractionType::Workbench => {
+                write!(f, "Work on gun")
+            }
+            InteractionType::TakePart => {
+                write!(f, "Take part")
+            }
+            InteractionType::Vendor => {
+                write!(f, "Buy ammo")
+            }
         }
     }
 }
@@ -36,7 +59,15 @@ impl Plugin for InteractionsPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             Update,
-            (set_interaction_state, draw_crosshair).run_if(in_state(crate::GameState::RunAndGun)),
+            (
+                set_interaction_state,
+                // Interact also closes menus, so it mustn't reopen them on the same press
+                interact
+                    .run_if(in_state(WorkbenchState::Closed))
+                    .run_if(in_state(VendorState::Closed)),
+                draw_crosshair,
+            )
+                .run_if(in_state(crate::GameState::RunAndGun)),
         )
         .add_plugins(ShapePlugin::new(ShapeConfig {
             disable_laa: true,
@@ -114,3 +145,33 @@ fn set_interaction_state(
         }
     }
 }
+
+fn interact(
+    player_query: Query<&ActionState<PlayerAction>, With<crate::player::Player>>,
+    interactable_query: Query<&Interactable>,
+    player_stats: Res<crate::player::PlayerStats>,
+    mut sleep_events: EventWriter<SleepEvent>,
+    mut workbench_events: EventWriter<UseWorkbench>,
+    mut take_part_events: EventWriter<TakePart>,
+    mut vendor_events: EventWriter<UseVendor>,
+) {
+    let Ok(action_state) = player_query.get_single() else {
+        return;
+    };
+    if !action_state.just_pressed(PlayerAction::Interact) {
+        return;
+    }
+
+    let Some(entity) = player_stats.interacted() else {
+        return;
+    };
+    if let Ok(interactable) = interactable_query.get(entity) {
+        match interactable.0 {
+            InteractionType::Sleep => sleep_events.send(SleepEvent { hour: 8, minute: 0 }),
+            InteractionType::Workbench => workbench_events.send(UseWorkbench),
+            InteractionType::TakePart => take_part_events.send(TakePart(entity)),
+            InteractionType::Vendor => vendor_events.send(UseVendor(entity)),
+            _ => {}
+        }
+    }
+}
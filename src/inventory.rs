@@ -1,6 +1,6 @@
 use crate::{
     camera::CameraFocus,
-    weapon::{FireResult, Gun, TriggerMode},
+    weapon::{FireResult, Gun, TriggerInput, TriggerMode},
 };
 use bevy::{prelude::*, utils::HashMap};
 use std::time::Duration;
@@ -10,26 +10,146 @@ pub struct Inventory {
     items: HashMap<ItemId, Item>,
 }
 
+const HOLSTER_SECONDS: f32 = 0.25;
+const DRAW_SECONDS: f32 = 0.35;
+
+/// The guns the player carries, one of which is drawn at a time
 #[derive(Resource, Default)]
 pub struct Belt {
-    pub gun: Gun,
+    slots: Vec<Gun>,
+    active: usize,
+    switch: Option<WeaponSwitch>,
+    /// Stands in while the belt is empty
+    unarmed: Gun,
+}
+
+struct WeaponSwitch {
+    to: usize,
+    drawing: bool,
+    timer: Timer,
 }
 
 impl Belt {
+    /// Puts `gun` in the next free slot and returns that slot
+    pub fn add(&mut self, gun: Gun) -> usize {
+        self.slots.push(gun);
+        self.slots.len() - 1
+    }
+
+    pub fn gun(&self) -> &Gun {
+        self.slots.get(self.active).unwrap_or(&self.unarmed)
+    }
+
+    pub fn gun_mut(&mut self) -> &mut Gun {
+        self.slots.get_mut(self.active).unwrap_or(&mut self.unarmed)
+    }
+
+    pub fn guns_mut(&mut self) -> impl Iterator<Item = &mut Gun> {
+        self.slots.iter_mut()
+    }
+
+    pub fn active_slot(&self) -> usize {
+        self.active
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Holsters the current gun and then draws the one in `slot`
+    pub fn select(&mut self, slot: usize) {
+        if slot >= self.slots.len() {
+            return;
+        }
+
+        match &mut self.switch {
+            Some(switch) if !switch.drawing => switch.to = slot,
+            Some(_) => self.start_switch(slot),
+            None if slot != self.active => self.start_switch(slot),
+            None => {}
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.slots.is_empty() {
+            self.select((self.target_slot() + 1) % self.slots.len());
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        if !self.slots.is_empty() {
+            let len = self.slots.len();
+            self.select((self.target_slot() + len - 1) % len);
+        }
+    }
+
+    pub fn is_switching(&self) -> bool {
+        self.switch.is_some()
+    }
+
+    /// How far the drawn gun is put away, from 0 when ready to 1 when fully holstered
+    pub fn holster_amount(&self) -> f32 {
+        match &self.switch {
+            Some(switch) if switch.drawing => 1.0 - switch.timer.percent(),
+            Some(switch) => switch.timer.percent(),
+            None => 0.0,
+        }
+    }
+
+    fn target_slot(&self) -> usize {
+        self.switch.as_ref().map_or(self.active, |switch| switch.to)
+    }
+
+    fn start_switch(&mut self, slot: usize) {
+        self.switch = Some(WeaponSwitch {
+            to: slot,
+            drawing: false,
+            timer: Timer::from_seconds(HOLSTER_SECONDS, TimerMode::Once),
+        });
+    }
+
     pub fn get_trigger_mode(&self) -> TriggerMode {
-        self.gun.get_trigger_mode()
+        self.gun().get_trigger_mode()
     }
 
+    /// The drawn gun ticks fully, holstered ones only finish their reloads
     pub fn tick(&mut self, delta: Duration) {
-        self.gun.tick(delta);
+        let active = self.active;
+        for (slot, gun) in self.slots.iter_mut().enumerate() {
+            if slot != active {
+                gun.tick_reload(delta);
+            }
+        }
+        self.gun_mut().tick(delta);
+
+        let Some(switch) = &mut self.switch else {
+            return;
+        };
+        if !switch.timer.tick(delta).finished() {
+            return;
+        }
+        if switch.drawing {
+            self.switch = None;
+        } else {
+            self.active = switch.to;
+            switch.drawing = true;
+            switch.timer = Timer::from_seconds(DRAW_SECONDS, TimerMode::Once);
+        }
     }
 
-    pub fn fire(&mut self, camera_focus: Res<CameraFocus>) -> FireResult {
-        self.gun.fire(camera_focus)
+    pub fn pull_trigger(&mut self, input: TriggerInput, camera_focus: &CameraFocus) -> FireResult {
+        if self.is_switching() {
+            return FireResult::NoAction;
+        }
+        self.gun_mut().pull_trigger(input, camera_focus)
     }
 
     pub fn get_spread(&self) -> f32 {
-        self.gun.get_spread()
+        self.gun().get_spread()
     }
 }
 
@@ -1,3 +1,4 @@
+use bevy::asset::ChangeWatcher;
 use bevy::prelude::*;
 use bevy::utils::HashMap;
 use bevy_asset_loader::prelude::*;
@@ -5,13 +6,20 @@ use bevy_gltf_components::ComponentsFromGltfPlugin;
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use bevy_rapier3d::prelude::*;
 use leafwing_input_manager::prelude::InputManagerPlugin;
+use std::time::Duration;
 
 pub mod interactions;
 
+pub mod map;
+
 pub mod camera;
 
 pub mod clock;
 
+pub mod daylight;
+
+pub mod scheduler;
+
 pub mod movement;
 
 pub mod collision;
@@ -26,6 +34,8 @@ pub mod quest;
 
 pub mod settings;
 
+pub mod sleep;
+
 pub mod dialogue;
 
 pub mod weapon;
@@ -34,22 +44,50 @@ pub mod inventory;
 
 pub mod player;
 
+pub mod projectile;
+
+pub mod ammo;
+
+pub mod parts;
+
+pub mod workbench;
+
+pub mod recoil;
+
+pub mod health;
+
 pub mod money;
 
+pub mod notifications;
+
+pub mod messages;
+
 pub mod audio;
 
+pub mod bank;
+
 pub mod hud;
 
 pub mod phone;
 
+pub mod phone_camera;
+
+pub mod rules;
+
 pub mod shooting;
 
+pub mod surface;
+
+pub mod decals;
+
 #[derive(States, PartialEq, Eq, Debug, Clone, Hash, Default)]
 pub enum GameState {
     #[default]
     Loading,
     MainMenu,
     RunAndGun,
+    Bankrupt,
+    DebtFree,
 }
 
 #[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
@@ -68,8 +106,47 @@ pub struct AssetCache {
     #[asset(path = "stool.glb#Scene0")]
     pub stool: Handle<Scene>,
     pub screen_material: Handle<StandardMaterial>,
+    #[asset(path = "data/day_cycle.daylight.ron")]
+    pub daylight: Handle<daylight::DaylightCurves>,
+    #[asset(path = "data/texts.messages.ron")]
+    pub messages: Handle<messages::MessageScript>,
     #[asset(paths("buildings/teahouse.glb#Scene0"), collection(typed, mapped))]
     pub buildings: HashMap<String, Handle<Scene>>,
+    #[asset(
+        paths(
+            "data/guns/rifle.gun.ron",
+            "data/guns/pistol.gun.ron",
+            "data/guns/shotgun.gun.ron",
+            "data/guns/nailgun.gun.ron"
+        ),
+        collection(typed, mapped)
+    )]
+    pub guns: HashMap<String, Handle<weapon::GunDefinition>>,
+    #[asset(
+        paths(
+            "data/parts/long_barrel.part.ron",
+            "data/parts/suppressor.part.ron",
+            "data/parts/choke.part.ron",
+            "data/parts/extended_clip.part.ron",
+            "data/parts/heavy_receiver.part.ron",
+            "data/parts/hair_trigger.part.ron",
+            "data/parts/auto_sear.part.ron"
+        ),
+        collection(typed, mapped)
+    )]
+    pub parts: HashMap<String, Handle<parts::GunPart>>,
+    #[asset(
+        paths(
+            "decals/default.png",
+            "decals/wood.png",
+            "decals/metal.png",
+            "decals/concrete.png",
+            "decals/glass.png",
+            "decals/flesh.png"
+        ),
+        collection(typed, mapped)
+    )]
+    pub decals: HashMap<String, Handle<Image>>,
 }
 
 fn main() {
@@ -79,7 +156,15 @@ fn main() {
             LoadingState::new(GameState::Loading).continue_to_state(GameState::RunAndGun),
         )
         .add_collection_to_loading_state::<_, AssetCache>(GameState::Loading)
-        .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()))
+        .add_plugins(
+            DefaultPlugins
+                .set(ImagePlugin::default_nearest())
+                // Hot reloads assets like gun definitions while the game runs
+                .set(AssetPlugin {
+                    watch_for_changes: ChangeWatcher::with_delay(Duration::from_millis(200)),
+                    ..default()
+                }),
+        )
         .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
         .add_plugins(InputManagerPlugin::<input::PlayerAction>::default())
         .add_plugins(WorldInspectorPlugin::default())
@@ -98,16 +183,39 @@ fn main() {
         .add_plugins((
             hud::HudPlugin,
             clock::ClockPlugin,
+            daylight::DaylightPlugin,
+            scheduler::SchedulerPlugin,
             camera::PlayerCameraPlugin,
             movement::MovementPlugin,
             //dialogue::DialoguePlugin,
             interactions::InteractionsPlugin,
             player::PlayerPlugin,
             settings::UserSettingsPlugin,
-            shooting::ShootingPlugin,
             audio::AudioPlugin,
             money::MoneyPlugin,
+            rules::RulesPlugin,
+            quest::QuestPlugin,
+            sleep::SleepPlugin,
+        ))
+        // Weapons
+        .add_plugins((
+            shooting::ShootingPlugin,
+            projectile::ProjectilePlugin,
+            recoil::RecoilPlugin,
+            workbench::WorkbenchPlugin,
+            ammo::AmmoPlugin,
+            health::HealthPlugin,
+            surface::SurfacePlugin,
+            decals::DecalPlugin,
+        ))
+        // Phone and its apps
+        .add_plugins((
             phone::PhonePlugin,
+            notifications::NotificationsPlugin,
+            bank::BankPlugin,
+            messages::MessagesPlugin,
+            phone_camera::PhoneCameraPlugin,
+            map::MapPlugin,
         ))
         .run();
 }
@@ -118,12 +226,6 @@ fn setup(
     asset_cache: Res<AssetCache>,
     assets: Res<AssetServer>,
 ) {
-    // Ambient Light
-    commands.insert_resource(AmbientLight {
-        color: Color::WHITE,
-        brightness: 0.2,
-    });
-
     // Ground
     commands
         .spawn(PbrBundle {
@@ -133,6 +235,7 @@ fn setup(
             ..default()
         })
         .insert(Collider::cuboid(5.0, 0.25, 5.0))
+        .insert(surface::SurfaceMaterial::concrete())
         .insert(RigidBody::Fixed);
     // Cube Stack
     commands
@@ -144,6 +247,8 @@ fn setup(
         })
         .insert(Collider::cuboid(0.5, 0.5, 0.5))
         .insert(shooting::Shootable)
+        .insert(health::Health::new(40.0))
+        .insert(surface::SurfaceMaterial::wood())
         .insert(RigidBody::Dynamic)
         .insert(interactions::Interactable(
             interactions::InteractionType::Pickup,
@@ -157,6 +262,9 @@ fn setup(
         })
         .insert(Collider::cuboid(0.5, 0.5, 0.5))
         .insert(shooting::Shootable)
+        .insert(health::Health::new(40.0))
+        .insert(surface::SurfaceMaterial::wood())
+        .insert(health::Armor(0.5))
         .insert(RigidBody::Dynamic)
         .insert(interactions::Interactable(
             interactions::InteractionType::Pickup,
@@ -171,6 +279,9 @@ fn setup(
         })
         .insert(Collider::cuboid(0.5, 0.5, 0.5))
         .insert(shooting::Shootable)
+        .insert(health::Health::new(40.0))
+        .insert(surface::SurfaceMaterial::wood())
+        .insert(health::Loot(money::Money::from_dollars(5)))
         .insert(RigidBody::Dynamic)
         .insert(interactions::Interactable(
             interactions::InteractionType::Pickup,
@@ -183,6 +294,105 @@ fn setup(
         ..default()
     });
 
+    // Bed
+    commands
+        .spawn(PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::Box::new(1.2, 0.5, 2.2))),
+            material: asset_cache.check_material.clone(),
+            transform: Transform::from_xyz(-3.0, 0.25, 3.0),
+            ..default()
+        })
+        .insert(Collider::cuboid(0.6, 0.25, 1.1))
+        .insert(RigidBody::Fixed)
+        .insert(interactions::Interactable(
+            interactions::InteractionType::Sleep,
+        ))
+        .insert(Name::from("Bed"));
+
+    // Workbench, with a few parts lying around it
+    commands
+        .spawn(PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::Box::new(2.0, 1.0, 0.8))),
+            material: asset_cache.check_material.clone(),
+            transform: Transform::from_xyz(-3.0, 0.5, -2.0),
+            ..default()
+        })
+        .insert(Collider::cuboid(1.0, 0.5, 0.4))
+        .insert(surface::SurfaceMaterial::wood())
+        .insert(RigidBody::Fixed)
+        .insert(interactions::Interactable(
+            interactions::InteractionType::Workbench,
+        ))
+        .insert(Name::from("Workbench"));
+
+    let part_pickups = [
+        ("data/parts/long_barrel.part.ron", money::Money::ZERO),
+        ("data/parts/extended_clip.part.ron", money::Money::ZERO),
+        (
+            "data/parts/hair_trigger.part.ron",
+            money::Money::from_dollars(15),
+        ),
+        (
+            "data/parts/heavy_receiver.part.ron",
+            money::Money::from_dollars(25),
+        ),
+    ];
+    for (index, (path, price)) in part_pickups.into_iter().enumerate() {
+        let Some(part) = asset_cache.parts.get(path) else {
+            continue;
+        };
+        commands
+            .spawn(PbrBundle {
+                mesh: meshes.add(Mesh::from(shape::Box::new(0.3, 0.2, 0.3))),
+                material: asset_cache.check_material.clone(),
+                transform: Transform::from_xyz(-3.6 + index as f32 * 0.4, 1.1, -2.0),
+                ..default()
+            })
+            .insert(Collider::cuboid(0.15, 0.1, 0.15))
+            .insert(RigidBody::Fixed)
+            .insert(interactions::Interactable(
+                interactions::InteractionType::TakePart,
+            ))
+            .insert(workbench::PartPickup {
+                part: part.clone(),
+                price,
+            })
+            .insert(Name::from("Part"));
+    }
+
+    // Ammo vendor
+    {
+        use ammo::{AmmoListing, AmmoType::*, Caliber::*};
+        commands
+            .spawn(PbrBundle {
+                mesh: meshes.add(Mesh::from(shape::Box::new(1.5, 1.5, 0.8))),
+                material: asset_cache.check_material.clone(),
+                transform: Transform::from_xyz(3.0, 0.75, -3.0),
+                ..default()
+            })
+            .insert(Collider::cuboid(0.75, 0.75, 0.4))
+            .insert(surface::SurfaceMaterial::metal())
+            .insert(RigidBody::Fixed)
+            .insert(interactions::Interactable(
+                interactions::InteractionType::Vendor,
+            ))
+            .insert(ammo::AmmoVendor {
+                name: "Corner Gun Shop".to_string(),
+                listings: vec![
+                    AmmoListing::new(Rifle, Standard, 30),
+                    AmmoListing::new(Rifle, HollowPoint, 30),
+                    AmmoListing::new(Rifle, ArmorPiercing, 30),
+                    AmmoListing::new(Rifle, Reloaded, 60),
+                    AmmoListing::new(Pistol, Standard, 24),
+                    AmmoListing::new(Pistol, HollowPoint, 24),
+                    AmmoListing::new(Shell, Standard, 12),
+                    AmmoListing::new(Shell, Reloaded, 24),
+                    AmmoListing::new(Nail, Standard, 100),
+                ],
+            })
+            .insert(Name::from("Ammo Vendor"));
+    }
+
     //Stool
     commands.spawn(SceneBundle {
         scene: asset_cache.stool.clone(),
@@ -197,7 +407,8 @@ fn setup(
                 transform: Transform::from_xyz(10.0, 0.0, 0.0),
                 ..default()
             })
-            .insert(Name::from("Teahouse"));
+            .insert(Name::from("Teahouse"))
+            .insert(phone_camera::PhotoSubject("Teahouse".to_string()));
     }
 }
 
@@ -0,0 +1,475 @@
+use bevy::{
+    prelude::*,
+    render::{
+        camera::{RenderTarget, ScalingMode},
+        render_resource::{
+            Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+        },
+        view::RenderLayers,
+    },
+    sprite::Anchor,
+};
+
+use crate::{
+    camera::{CameraFocus, PrimaryCamera},
+    notifications::{Notification, PostNotification},
+    phone::{
+        phone_app_open, AddPhoneApp, PhoneApp, PhoneAppInput, PhoneApps, PhoneInput,
+        PhoneNavigation, PhoneState,
+    },
+    AssetCache, GameState,
+};
+
+pub struct MapPlugin;
+
+impl Plugin for MapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_phone_app::<MapApp>()
+            .init_resource::<MapSelection>()
+            .add_systems(
+                OnEnter(GameState::RunAndGun),
+                (spawn_map_camera, spawn_waypoint_marker),
+            )
+            .add_systems(
+                Update,
+                (
+                    mark_buildings,
+                    spawn_map_ui,
+                    follow_camera_focus,
+                    map_input,
+                    update_map_markers.run_if(phone_app_open::<MapApp>),
+                    update_map_ui.run_if(phone_app_open::<MapApp>),
+                    reach_waypoint,
+                    update_waypoint_marker,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::RunAndGun)),
+            );
+    }
+}
+
+#[derive(Component, Default)]
+pub struct MapApp;
+
+impl PhoneApp for MapApp {
+    const NAME: &'static str = "Map";
+    const ICON_COLOR: Color = Color::rgb(0.3, 0.6, 0.85);
+}
+
+const MAP_IMAGE_SIZE: Extent3d = Extent3d {
+    width: 256,
+    height: 256,
+    depth_or_array_layers: 1,
+};
+/// World units shown across the map
+const MAP_SPAN: f32 = 80.0;
+/// Size of the map on the phone screen
+const MAP_SCREEN_SIZE: f32 = 200.0;
+const MAP_SCREEN_CENTER: Vec2 = Vec2::new(-140.0, -20.0);
+const MAP_CAMERA_HEIGHT: f32 = 150.0;
+const WAYPOINT_REACHED_DISTANCE: f32 = 4.0;
+
+/// Something worth listing on the map, named after its `Name`
+#[derive(Component)]
+pub struct PointOfInterest;
+
+/// Where the player asked to be guided to from the map
+#[derive(Resource, Clone, Debug)]
+pub struct Waypoint {
+    pub name: String,
+    pub position: Vec3,
+}
+
+#[derive(Resource, Default)]
+pub struct MapSelection(usize);
+
+#[derive(Component)]
+struct MapCamera;
+
+#[derive(Component)]
+struct PlayerMarker;
+
+#[derive(Component)]
+struct PoiMarker(Entity);
+
+#[derive(Component)]
+struct MapListText;
+
+#[derive(Component)]
+struct WaypointMarker;
+
+#[derive(Component)]
+struct WaypointLabel;
+
+#[derive(Resource)]
+struct MapImage(Handle<Image>);
+
+fn spawn_map_camera(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: Some("map"),
+            size: MAP_IMAGE_SIZE,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..default()
+    };
+    image.resize(MAP_IMAGE_SIZE);
+    let image_handle = images.add(image);
+
+    commands.spawn((
+        Camera3dBundle {
+            camera: Camera {
+                order: -1,
+                target: RenderTarget::Image(image_handle.clone()),
+                is_active: false,
+                ..default()
+            },
+            camera_3d: Camera3d {
+                clear_color: bevy::core_pipeline::clear_color::ClearColorConfig::Custom(
+                    Color::rgb(0.1, 0.12, 0.1),
+                ),
+                ..default()
+            },
+            projection: Projection::Orthographic(OrthographicProjection {
+                scaling_mode: ScalingMode::Fixed {
+                    width: MAP_SPAN,
+                    height: MAP_SPAN,
+                },
+                far: MAP_CAMERA_HEIGHT * 2.0,
+                ..default()
+            }),
+            ..default()
+        },
+        RenderLayers::layer(0),
+        MapCamera,
+        Name::new("Map Camera"),
+    ));
+    commands.insert_resource(MapImage(image_handle));
+}
+
+/// Every building from the asset cache shows up on the map
+#[allow(clippy::type_complexity)]
+fn mark_buildings(
+    mut commands: Commands,
+    asset_cache: Res<AssetCache>,
+    scene_query: Query<(Entity, &Handle<Scene>), (Added<Handle<Scene>>, With<Name>)>,
+) {
+    for (entity, scene) in &scene_query {
+        if asset_cache
+            .buildings
+            .values()
+            .any(|building| building == scene)
+        {
+            commands.entity(entity).insert(PointOfInterest);
+        }
+    }
+}
+
+fn spawn_map_ui(
+    mut commands: Commands,
+    root_query: Query<Entity, Added<MapApp>>,
+    map_image: Res<MapImage>,
+) {
+    for root in &root_query {
+        commands.entity(root).with_children(|parent| {
+            parent.spawn(SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::splat(MAP_SCREEN_SIZE)),
+                    ..default()
+                },
+                texture: map_image.0.clone(),
+                transform: Transform::from_translation(MAP_SCREEN_CENTER.extend(1.0)),
+                ..default()
+            });
+            parent
+                .spawn(SpatialBundle::from_transform(Transform::from_translation(
+                    MAP_SCREEN_CENTER.extend(3.0),
+                )))
+                .insert(PlayerMarker)
+                .with_children(|marker| {
+                    marker.spawn(SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::YELLOW,
+                            custom_size: Some(Vec2::splat(8.0)),
+                            ..default()
+                        },
+                        ..default()
+                    });
+                    marker.spawn(SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::YELLOW,
+                            custom_size: Some(Vec2::new(3.0, 10.0)),
+                            ..default()
+                        },
+                        transform: Transform::from_xyz(0.0, 8.0, 0.0),
+                        ..default()
+                    });
+                });
+            parent
+                .spawn(Text2dBundle {
+                    text: Text::from_section(
+                        "",
+                        TextStyle {
+                            font_size: 18.0,
+                            ..default()
+                        },
+                    ),
+                    text_anchor: Anchor::TopLeft,
+                    transform: Transform::from_xyz(-20.0, 80.0, 1.0),
+                    ..default()
+                })
+                .insert(MapListText);
+        });
+    }
+}
+
+/// Centers the map on the player, with north up, and only renders it while it's on screen
+fn follow_camera_focus(
+    camera_focus: Res<CameraFocus>,
+    navigation: Res<PhoneNavigation>,
+    apps: Res<PhoneApps>,
+    phone_state: Res<State<PhoneState>>,
+    mut map_camera_query: Query<(&mut Camera, &mut Transform), With<MapCamera>>,
+    mut marker_query: Query<&mut Transform, (With<PlayerMarker>, Without<MapCamera>)>,
+) {
+    let active = phone_state.get().is_focused()
+        && navigation.current().is_some()
+        && navigation.current() == apps.id_of::<MapApp>();
+
+    if let Ok((mut camera, mut transform)) = map_camera_query.get_single_mut() {
+        if camera.is_active != active {
+            camera.is_active = active;
+        }
+        if active {
+            let center = camera_focus.origin() * Vec3::new(1.0, 0.0, 1.0);
+            *transform = Transform::from_translation(center + Vec3::Y * MAP_CAMERA_HEIGHT)
+                .looking_at(center, Vec3::NEG_Z);
+        }
+    }
+
+    // North is up on the map, so world -Z points up the screen
+    let forward = camera_focus.forward();
+    if let (true, Ok(mut transform)) = (active, marker_query.get_single_mut()) {
+        transform.rotation = Quat::from_rotation_z((-forward.x).atan2(-forward.z));
+    }
+}
+
+/// Where `position` lands on the phone screen, if it's within the map
+fn map_position(camera_focus: &CameraFocus, position: Vec3) -> Option<Vec2> {
+    let offset = position - camera_focus.origin();
+    let scaled = Vec2::new(offset.x, -offset.z) * (MAP_SCREEN_SIZE / MAP_SPAN);
+    (scaled.abs().max_element() <= MAP_SCREEN_SIZE / 2.0).then_some(MAP_SCREEN_CENTER + scaled)
+}
+
+fn sorted_points_of_interest<'a>(
+    poi_query: &'a Query<(Entity, &Name, &GlobalTransform), With<PointOfInterest>>,
+) -> Vec<(Entity, &'a Name, &'a GlobalTransform)> {
+    let mut points = poi_query.iter().collect::<Vec<_>>();
+    points.sort_by(|a, b| a.1.as_str().cmp(b.1.as_str()));
+    points
+}
+
+fn map_input(
+    mut commands: Commands,
+    mut app_inputs: EventReader<PhoneAppInput>,
+    apps: Res<PhoneApps>,
+    mut selection: ResMut<MapSelection>,
+    waypoint: Option<Res<Waypoint>>,
+    poi_query: Query<(Entity, &Name, &GlobalTransform), With<PointOfInterest>>,
+) {
+    let points = sorted_points_of_interest(&poi_query);
+    for input in apps.inputs_for::<MapApp>(&mut app_inputs) {
+        match input {
+            PhoneInput::Up => selection.0 = selection.0.saturating_sub(1),
+            PhoneInput::Down => selection.0 = (selection.0 + 1).min(points.len().saturating_sub(1)),
+            PhoneInput::Select => {
+                let Some((_, name, transform)) = points.get(selection.0) else {
+                    continue;
+                };
+                if waypoint
+                    .as_ref()
+                    .is_some_and(|waypoint| waypoint.name == name.as_str())
+                {
+                    commands.remove_resource::<Waypoint>();
+                } else {
+                    commands.insert_resource(Waypoint {
+                        name: name.to_string(),
+                        position: transform.translation(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn update_map_markers(
+    mut commands: Commands,
+    camera_focus: Res<CameraFocus>,
+    root_query: Query<Entity, With<MapApp>>,
+    poi_query: Query<(Entity, &GlobalTransform), With<PointOfInterest>>,
+    mut marker_query: Query<(Entity, &PoiMarker, &mut Transform, &mut Visibility)>,
+) {
+    let Ok(root) = root_query.get_single() else {
+        return;
+    };
+
+    for (marker_entity, marker, mut transform, mut visibility) in &mut marker_query {
+        let Ok((_, poi_transform)) = poi_query.get(marker.0) else {
+            commands.entity(marker_entity).despawn_recursive();
+            continue;
+        };
+        match map_position(&camera_focus, poi_transform.translation()) {
+            Some(position) => {
+                transform.translation = position.extend(2.0);
+                *visibility = Visibility::Inherited;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+
+    for (poi, _) in &poi_query {
+        if !marker_query.iter().any(|(_, marker, ..)| marker.0 == poi) {
+            commands.entity(root).with_children(|parent| {
+                parent
+                    .spawn(SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::ORANGE_RED,
+                            custom_size: Some(Vec2::splat(10.0)),
+                            ..default()
+                        },
+                        visibility: Visibility::Hidden,
+                        ..default()
+                    })
+                    .insert(PoiMarker(poi));
+            });
+        }
+    }
+}
+
+fn update_map_ui(
+    camera_focus: Res<CameraFocus>,
+    selection: Res<MapSelection>,
+    waypoint: Option<Res<Waypoint>>,
+    poi_query: Query<(Entity, &Name, &GlobalTransform), With<PointOfInterest>>,
+    mut text_query: Query<&mut Text, With<MapListText>>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+
+    let mut lines = vec!["Places".to_string()];
+    for (index, (_, name, transform)) in sorted_points_of_interest(&poi_query)
+        .into_iter()
+        .enumerate()
+    {
+        let cursor = if index == selection.0 { ">" } else { " " };
+        let flag = if waypoint
+            .as_ref()
+            .is_some_and(|waypoint| waypoint.name == name.as_str())
+        {
+            "*"
+        } else {
+            " "
+        };
+        let distance = transform.translation().distance(camera_focus.origin());
+        lines.push(format!("{}{} {} {:.0}m", cursor, flag, name, distance));
+    }
+    if lines.len() == 1 {
+        lines.push("Nothing nearby".to_string());
+    }
+    lines.push("[Select] Set waypoint".to_string());
+    text.sections[0].value = lines.join("\n");
+}
+
+fn reach_waypoint(
+    mut commands: Commands,
+    camera_focus: Res<CameraFocus>,
+    waypoint: Option<Res<Waypoint>>,
+) {
+    let Some(waypoint) = waypoint else {
+        return;
+    };
+    let offset = (waypoint.position - camera_focus.origin()) * Vec3::new(1.0, 0.0, 1.0);
+    if offset.length() < WAYPOINT_REACHED_DISTANCE {
+        commands.add(PostNotification(
+            Notification::new("Arrived", format!("Reached {}", waypoint.name)).from_app::<MapApp>(),
+        ));
+        commands.remove_resource::<Waypoint>();
+    }
+}
+
+fn spawn_waypoint_marker(mut commands: Commands) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            ..default()
+        })
+        .insert(WaypointMarker)
+        .with_children(|parent| {
+            parent.spawn(NodeBundle {
+                style: Style {
+                    width: Val::Px(12.0),
+                    height: Val::Px(12.0),
+                    ..default()
+                },
+                background_color: Color::YELLOW.into(),
+                ..default()
+            });
+            parent
+                .spawn(TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::YELLOW,
+                        ..default()
+                    },
+                ))
+                .insert(WaypointLabel);
+        });
+}
+
+/// Pins the waypoint over its spot in the world while it's in front of the camera
+fn update_waypoint_marker(
+    waypoint: Option<Res<Waypoint>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<PrimaryCamera>>,
+    mut marker_query: Query<(&mut Style, &mut Visibility), With<WaypointMarker>>,
+    mut label_query: Query<&mut Text, With<WaypointLabel>>,
+) {
+    let Ok((mut style, mut visibility)) = marker_query.get_single_mut() else {
+        return;
+    };
+
+    let viewport_position = waypoint.as_ref().and_then(|waypoint| {
+        let (camera, camera_transform) = camera_query.get_single().ok()?;
+        camera.world_to_viewport(camera_transform, waypoint.position + Vec3::Y * 2.0)
+    });
+    let (Some(waypoint), Some(position)) = (waypoint, viewport_position) else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    *visibility = Visibility::Inherited;
+    style.left = Val::Px(position.x - 6.0);
+    style.top = Val::Px(position.y - 6.0);
+
+    if let (Ok(mut text), Ok((_, camera_transform))) =
+        (label_query.get_single_mut(), camera_query.get_single())
+    {
+        let distance = waypoint.position.distance(camera_transform.translation());
+        text.sections[0].value = format!("{} {:.0}m", waypoint.name, distance);
+    }
+}
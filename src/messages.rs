@@ -0,0 +1,627 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    ecs::system::Command,
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    sprite::Anchor,
+    text::Text2dBounds,
+    utils::{BoxedFuture, HashSet},
+};
+use serde::Deserialize;
+
+use crate::{
+    clock::{Date, Phone},
+    money::{DebtEvent, DebtKind, Money, PayDebtCommand, TransactionCategory, Wallet},
+    notifications::{Notification, PostNotification},
+    phone::{phone_app_open, AddPhoneApp, PhoneApp, PhoneAppInput, PhoneApps, PhoneInput},
+    quest::{QuestId, QuestLog, QuestStatus, QuestStatusEvent},
+    scheduler::Scheduler,
+    AssetCache, GameState,
+};
+
+pub struct MessagesPlugin;
+
+impl Plugin for MessagesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_phone_app::<MessagesApp>()
+            .add_asset::<MessageScript>()
+            .init_asset_loader::<MessageScriptLoader>()
+            .add_event::<MessageReceived>()
+            .init_resource::<Inbox>()
+            .init_resource::<MessagesView>()
+            .add_systems(
+                Update,
+                (
+                    schedule_messages,
+                    send_debt_messages,
+                    spawn_messages_ui,
+                    messages_input,
+                    update_messages_ui.run_if(phone_app_open::<MessagesApp>),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::RunAndGun)),
+            );
+    }
+}
+
+#[derive(Component, Default)]
+pub struct MessagesApp;
+
+impl PhoneApp for MessagesApp {
+    const NAME: &'static str = "Messages";
+    const ICON_COLOR: Color = Color::rgb(0.2, 0.6, 0.9);
+}
+
+const VISIBLE_MESSAGES: usize = 5;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Contact {
+    pub id: String,
+    pub name: String,
+}
+
+/// When a scripted text arrives
+#[derive(Deserialize, Debug, Clone)]
+pub enum MessageTrigger {
+    /// `day` counts from the first day of the run
+    At {
+        day: u32,
+        hour: u8,
+        minute: u8,
+    },
+    MissedPayment(DebtKind),
+    PaidOff(DebtKind),
+    /// Only sent by another message's reply
+    Manual,
+}
+
+/// Something that happens when the player picks a reply
+#[derive(Deserialize, Debug, Clone)]
+pub enum MessageEffect {
+    Credit { amount: Money, memo: String },
+    Debit { amount: Money, memo: String },
+    PayDebt { debt: DebtKind, amount: Money },
+    Send { message: String, delay_minutes: u32 },
+    StartQuest { id: u16, description: String },
+}
+
+impl MessageEffect {
+    fn apply(&self, world: &mut World) {
+        let date = world.resource::<Phone>().date;
+        match self {
+            MessageEffect::Credit { amount, memo } => {
+                let notification = world.resource_mut::<Wallet>().credit(
+                    *amount,
+                    TransactionCategory::Income,
+                    date,
+                    memo.clone(),
+                );
+                notification.apply(world);
+            }
+            MessageEffect::Debit { amount, memo } => {
+                let notification = world.resource_mut::<Wallet>().debit(
+                    *amount,
+                    TransactionCategory::Other,
+                    date,
+                    memo.clone(),
+                );
+                notification.apply(world);
+            }
+            MessageEffect::PayDebt { debt, amount } => {
+                PayDebtCommand {
+                    debt: *debt,
+                    amount: *amount,
+                }
+                .apply(world);
+            }
+            MessageEffect::Send {
+                message,
+                delay_minutes: 0,
+            } => {
+                DeliverMessageCommand {
+                    message: message.clone(),
+                }
+                .apply(world);
+            }
+            MessageEffect::Send {
+                message,
+                delay_minutes,
+            } => {
+                let message = message.clone();
+                let mut scheduler = world.resource_mut::<Scheduler>();
+                let id = scheduler.after(*delay_minutes, format!("Text {}", message));
+                scheduler.with_callback(id, move |world, _| {
+                    DeliverMessageCommand {
+                        message: message.clone(),
+                    }
+                    .apply(world);
+                });
+            }
+            MessageEffect::StartQuest { id, description } => {
+                world
+                    .resource_mut::<QuestLog>()
+                    .discover(QuestId(*id), description.clone());
+                world.send_event(QuestStatusEvent {
+                    id: QuestId(*id),
+                    status: QuestStatus::Discovered,
+                });
+            }
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Reply {
+    pub text: String,
+    #[serde(default)]
+    pub effects: Vec<MessageEffect>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ScriptedMessage {
+    pub id: String,
+    pub from: String,
+    pub text: String,
+    pub trigger: MessageTrigger,
+    /// Whether the message can arrive again every time its trigger fires
+    #[serde(default)]
+    pub repeatable: bool,
+    #[serde(default)]
+    pub replies: Vec<Reply>,
+}
+
+/// Every contact and text in the game, loaded from a `.messages.ron` file
+#[derive(Deserialize, TypeUuid, TypePath, Debug, Clone)]
+#[uuid = "3e0f6f7c-4f5b-4b8e-9a3a-7d2c41e8b9a1"]
+pub struct MessageScript {
+    pub contacts: Vec<Contact>,
+    pub messages: Vec<ScriptedMessage>,
+}
+
+impl MessageScript {
+    pub fn message(&self, id: &str) -> Option<&ScriptedMessage> {
+        self.messages.iter().find(|message| message.id == id)
+    }
+
+    pub fn contact_name<'a>(&'a self, id: &'a str) -> &'a str {
+        self.contacts
+            .iter()
+            .find(|contact| contact.id == id)
+            .map(|contact| contact.name.as_str())
+            .unwrap_or(id)
+    }
+}
+
+#[derive(Default)]
+pub struct MessageScriptLoader;
+
+impl AssetLoader for MessageScriptLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let script = ron::de::from_bytes::<MessageScript>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(script));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["messages.ron"]
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TextMessage {
+    pub date: Date,
+    pub text: String,
+    pub incoming: bool,
+    /// The scripted message this came from, for looking up replies
+    pub message_id: Option<String>,
+    pub answered: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct Thread {
+    pub contact: String,
+    pub messages: Vec<TextMessage>,
+    pub unread: u32,
+}
+
+impl Thread {
+    /// The latest text if it's still waiting on an answer
+    pub fn awaiting_reply(&self) -> Option<&TextMessage> {
+        self.messages
+            .last()
+            .filter(|message| message.incoming && !message.answered && message.message_id.is_some())
+    }
+}
+
+/// Conversations, most recently active first
+#[derive(Resource, Default)]
+pub struct Inbox {
+    threads: Vec<Thread>,
+    delivered: HashSet<String>,
+}
+
+impl Inbox {
+    pub fn threads(&self) -> &[Thread] {
+        &self.threads
+    }
+
+    pub fn unread(&self) -> u32 {
+        self.threads.iter().map(|thread| thread.unread).sum()
+    }
+
+    pub fn thread(&self, contact: &str) -> Option<&Thread> {
+        self.threads.iter().find(|thread| thread.contact == contact)
+    }
+
+    pub fn was_delivered(&self, message: &str) -> bool {
+        self.delivered.contains(message)
+    }
+
+    /// Moves the contact's thread to the top, creating it if this is their first text
+    fn thread_mut(&mut self, contact: &str) -> &mut Thread {
+        let thread = match self
+            .threads
+            .iter()
+            .position(|thread| thread.contact == contact)
+        {
+            Some(index) => self.threads.remove(index),
+            None => Thread {
+                contact: contact.to_string(),
+                messages: Vec::new(),
+                unread: 0,
+            },
+        };
+        self.threads.insert(0, thread);
+        &mut self.threads[0]
+    }
+
+    /// Returns false if the message was already delivered and can't repeat
+    pub fn receive(&mut self, message: &ScriptedMessage, date: Date) -> bool {
+        if !message.repeatable && self.delivered.contains(&message.id) {
+            return false;
+        }
+        self.delivered.insert(message.id.clone());
+
+        let thread = self.thread_mut(&message.from);
+        thread.messages.push(TextMessage {
+            date,
+            text: message.text.clone(),
+            incoming: true,
+            message_id: (!message.replies.is_empty()).then(|| message.id.clone()),
+            answered: false,
+        });
+        thread.unread += 1;
+        true
+    }
+
+    fn send_reply(&mut self, contact: &str, text: &str, date: Date) {
+        if let Some(thread) = self
+            .threads
+            .iter_mut()
+            .find(|thread| thread.contact == contact)
+        {
+            if let Some(last) = thread.messages.last_mut() {
+                last.answered = true;
+            }
+            thread.messages.push(TextMessage {
+                date,
+                text: text.to_string(),
+                incoming: false,
+                message_id: None,
+                answered: true,
+            });
+        }
+    }
+
+    fn mark_read(&mut self, contact: &str) {
+        if let Some(thread) = self
+            .threads
+            .iter_mut()
+            .find(|thread| thread.contact == contact)
+        {
+            thread.unread = 0;
+        }
+    }
+}
+
+/// Sent whenever a text lands in the inbox
+#[derive(Event, Clone, Debug)]
+pub struct MessageReceived {
+    pub contact: String,
+    pub text: String,
+}
+
+/// Puts a scripted message into the inbox if the script has it and it hasn't already arrived.
+pub struct DeliverMessageCommand {
+    pub message: String,
+}
+
+impl Command for DeliverMessageCommand {
+    fn apply(self, world: &mut World) {
+        let handle = world.resource::<AssetCache>().messages.clone();
+        let Some((message, contact)) = world
+            .resource::<Assets<MessageScript>>()
+            .get(&handle)
+            .and_then(|script| {
+                let message = script.message(&self.message)?;
+                Some((
+                    message.clone(),
+                    script.contact_name(&message.from).to_string(),
+                ))
+            })
+        else {
+            warn!("No text message with id {}", self.message);
+            return;
+        };
+
+        let date = world.resource::<Phone>().date;
+        if world.resource_mut::<Inbox>().receive(&message, date) {
+            PostNotification(Notification::new(&contact, &message.text).from_app::<MessagesApp>())
+                .apply(world);
+            world.send_event(MessageReceived {
+                contact,
+                text: message.text,
+            });
+        }
+    }
+}
+
+/// Applies the effects of a reply once it's been sent
+struct ApplyEffectsCommand(Vec<MessageEffect>);
+
+impl Command for ApplyEffectsCommand {
+    fn apply(self, world: &mut World) {
+        for effect in &self.0 {
+            effect.apply(world);
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct MessagesView {
+    /// Contact whose conversation is open, or the thread list when `None`
+    thread: Option<String>,
+    selected: usize,
+    reply: usize,
+}
+
+#[derive(Component)]
+struct MessagesText;
+
+fn schedule_messages(
+    mut commands: Commands,
+    mut scheduled: Local<bool>,
+    asset_cache: Res<AssetCache>,
+    scripts: Res<Assets<MessageScript>>,
+    phone: Res<Phone>,
+    mut scheduler: ResMut<Scheduler>,
+) {
+    if *scheduled {
+        return;
+    }
+    let Some(script) = scripts.get(&asset_cache.messages) else {
+        return;
+    };
+    *scheduled = true;
+
+    let now = phone.date.total_minutes();
+    for message in &script.messages {
+        if let MessageTrigger::At { day, hour, minute } = message.trigger {
+            let at = day as u64 * 24 * 60 + hour as u64 * 60 + minute as u64;
+            let deliver = DeliverMessageCommand {
+                message: message.id.clone(),
+            };
+            if at <= now {
+                commands.add(deliver);
+                continue;
+            }
+
+            let id = scheduler.at(
+                phone.date.add_minutes((at - now) as u32),
+                format!("Text {}", message.id),
+            );
+            let message = message.id.clone();
+            scheduler.with_callback(id, move |world, _| {
+                DeliverMessageCommand {
+                    message: message.clone(),
+                }
+                .apply(world);
+            });
+        }
+    }
+}
+
+fn send_debt_messages(
+    mut commands: Commands,
+    mut debt_events: EventReader<DebtEvent>,
+    asset_cache: Res<AssetCache>,
+    scripts: Res<Assets<MessageScript>>,
+) {
+    let Some(script) = scripts.get(&asset_cache.messages) else {
+        return;
+    };
+
+    for event in debt_events.iter() {
+        for message in &script.messages {
+            let triggered = match (&message.trigger, event) {
+                (MessageTrigger::MissedPayment(kind), DebtEvent::MissedPayment { debt, .. }) => {
+                    kind == debt
+                }
+                (MessageTrigger::PaidOff(kind), DebtEvent::PaidOff { debt }) => kind == debt,
+                _ => false,
+            };
+            if triggered {
+                commands.add(DeliverMessageCommand {
+                    message: message.id.clone(),
+                });
+            }
+        }
+    }
+}
+
+fn spawn_messages_ui(mut commands: Commands, root_query: Query<Entity, Added<MessagesApp>>) {
+    for root in &root_query {
+        commands.entity(root).with_children(|parent| {
+            parent
+                .spawn(Text2dBundle {
+                    text: Text::from_section(
+                        "",
+                        TextStyle {
+                            font_size: 18.0,
+                            ..default()
+                        },
+                    ),
+                    text_anchor: Anchor::TopLeft,
+                    text_2d_bounds: Text2dBounds {
+                        size: Vec2::new(490.0, 190.0),
+                    },
+                    transform: Transform::from_xyz(-245.0, 90.0, 1.0),
+                    ..default()
+                })
+                .insert(MessagesText);
+        });
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn messages_input(
+    mut commands: Commands,
+    mut app_inputs: EventReader<PhoneAppInput>,
+    apps: Res<PhoneApps>,
+    asset_cache: Res<AssetCache>,
+    scripts: Res<Assets<MessageScript>>,
+    phone: Res<Phone>,
+    mut inbox: ResMut<Inbox>,
+    mut view: ResMut<MessagesView>,
+) {
+    for input in apps.inputs_for::<MessagesApp>(&mut app_inputs) {
+        match (view.thread.clone(), input) {
+            (None, PhoneInput::Up) => view.selected = view.selected.saturating_sub(1),
+            (None, PhoneInput::Down) => {
+                view.selected = (view.selected + 1).min(inbox.threads().len().saturating_sub(1))
+            }
+            (None, PhoneInput::Select | PhoneInput::Right) => {
+                if let Some(contact) = inbox
+                    .threads()
+                    .get(view.selected)
+                    .map(|thread| thread.contact.clone())
+                {
+                    inbox.mark_read(&contact);
+                    view.thread = Some(contact);
+                    view.reply = 0;
+                }
+            }
+            (Some(_), PhoneInput::Left) => view.thread = None,
+            (Some(_), PhoneInput::Up) => view.reply = view.reply.saturating_sub(1),
+            (Some(_), PhoneInput::Down) => view.reply += 1,
+            (Some(contact), PhoneInput::Select) => {
+                let Some(reply) = inbox
+                    .thread(&contact)
+                    .and_then(|thread| thread.awaiting_reply())
+                    .and_then(|message| message.message_id.as_deref())
+                    .and_then(|id| scripts.get(&asset_cache.messages)?.message(id))
+                    .and_then(|message| message.replies.get(view.reply))
+                    .cloned()
+                else {
+                    continue;
+                };
+
+                inbox.send_reply(&contact, &reply.text, phone.date);
+                commands.add(ApplyEffectsCommand(reply.effects));
+                view.reply = 0;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn update_messages_ui(
+    mut inbox: ResMut<Inbox>,
+    mut view: ResMut<MessagesView>,
+    asset_cache: Res<AssetCache>,
+    scripts: Res<Assets<MessageScript>>,
+    mut text_query: Query<&mut Text, With<MessagesText>>,
+) {
+    let Some(script) = scripts.get(&asset_cache.messages) else {
+        return;
+    };
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+
+    // Texts arriving in the open conversation are read straight away
+    if let Some(contact) = &view.thread {
+        if inbox
+            .thread(contact)
+            .is_some_and(|thread| thread.unread > 0)
+        {
+            inbox.mark_read(contact);
+        }
+    }
+
+    let mut lines = Vec::new();
+    match view
+        .thread
+        .as_deref()
+        .and_then(|contact| inbox.thread(contact))
+    {
+        None => {
+            if inbox.threads().is_empty() {
+                lines.push("No messages".to_string());
+            }
+            for (index, thread) in inbox.threads().iter().enumerate() {
+                let cursor = if index == view.selected { ">" } else { " " };
+                let unread = match thread.unread {
+                    0 => String::new(),
+                    count => format!(" ({})", count),
+                };
+                let preview = thread
+                    .messages
+                    .last()
+                    .map(|message| message.text.chars().take(28).collect::<String>())
+                    .unwrap_or_default();
+                lines.push(format!(
+                    "{} {}{}: {}",
+                    cursor,
+                    script.contact_name(&thread.contact),
+                    unread,
+                    preview
+                ));
+            }
+        }
+        Some(thread) => {
+            let name = script.contact_name(&thread.contact);
+            lines.push(format!("< {}", name));
+            let skip = thread.messages.len().saturating_sub(VISIBLE_MESSAGES);
+            for message in &thread.messages[skip..] {
+                let sender = if message.incoming { name } else { "You" };
+                lines.push(format!("{}: {}", sender, message.text));
+            }
+
+            let replies = thread
+                .awaiting_reply()
+                .and_then(|message| message.message_id.as_deref())
+                .and_then(|id| script.message(id))
+                .map(|message| message.replies.as_slice())
+                .unwrap_or_default();
+            if !replies.is_empty() {
+                view.reply = view.reply.min(replies.len() - 1);
+            }
+            for (index, reply) in replies.iter().enumerate() {
+                let cursor = if index == view.reply { ">" } else { " " };
+                lines.push(format!("{} [{}]", cursor, reply.text));
+            }
+        }
+    }
+
+    let value = lines.join("\n");
+    if text.sections[0].value != value {
+        text.sections[0].value = value;
+    }
+}
@@ -1,158 +1,475 @@
+use crate::clock::{Date, Day, HourPassed, Phone};
 use crate::hud::WalletDisplay;
+use crate::notifications::{Notification, PostNotification};
 use bevy::ecs::system::Command;
 use bevy::prelude::*;
-use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+use bevy::utils::HashMap;
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
 
 pub struct MoneyPlugin;
 
 impl Plugin for MoneyPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(Debts::default())
+        app.add_event::<DebtEvent>()
+            .insert_resource(Debts::default())
             .insert_resource(Wallet::default())
+            .insert_resource(MoneyLocale::default())
             .add_systems(
                 Update,
-                (wallet_tracking, pop_up_movement).run_if(in_state(crate::GameState::RunAndGun)),
+                (wallet_tracking, process_debts).run_if(in_state(crate::GameState::RunAndGun)),
             );
     }
 }
 
 fn wallet_tracking(
     wallet: Res<Wallet>,
+    locale: Res<MoneyLocale>,
     mut wallet_display_query: Query<&mut Text, With<WalletDisplay>>,
 ) {
     for mut text in &mut wallet_display_query {
-        text.sections[1].value = wallet.funds.to_string();
+        text.sections[1].value = wallet.funds.localized(&locale).to_string();
     }
 }
 
-fn pop_up_movement(
-    mut commands: Commands,
-    time: Res<Time>,
-    mut pop_up_query: Query<(Entity, &mut PopUp, &mut Transform)>,
-) {
-    for (entity, mut popup, mut transform) in &mut pop_up_query {
-        let frequency = 5.0;
-        let phase = 15.0;
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub enum DebtKind {
+    Medical,
+    Rent,
+    Utilities,
+}
 
-        let x_offset =
-            popup.starting_x + ((time.elapsed_seconds() * frequency + phase).sin()) * 50.0;
-        transform.translation -= Vec3::Y * 30.0 * time.delta_seconds();
-        transform.translation.x = x_offset;
-        popup.tick(time.delta());
-        if popup.finished() {
-            commands.entity(entity).despawn_recursive();
+impl DebtKind {
+    pub fn category(&self) -> TransactionCategory {
+        match self {
+            Self::Medical => TransactionCategory::Medical,
+            Self::Rent => TransactionCategory::Rent,
+            Self::Utilities => TransactionCategory::Utilities,
         }
     }
 }
 
+impl std::fmt::Display for DebtKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let string_representation = match self {
+            Self::Medical => "Medical",
+            Self::Rent => "Rent",
+            Self::Utilities => "Utilities",
+        };
+        write!(f, "{}", string_representation)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Compounding {
+    Hourly,
+    Daily,
+    Weekly,
+}
+
+impl Compounding {
+    fn periods_per_year(&self) -> i64 {
+        match self {
+            Self::Hourly => 365 * 24,
+            Self::Daily => 365,
+            Self::Weekly => 52,
+        }
+    }
+
+    fn is_period_end(&self, date: &Date) -> bool {
+        match self {
+            Self::Hourly => true,
+            Self::Daily => date.hour == 0,
+            Self::Weekly => date.hour == 0 && date.day == Day::Sunday,
+        }
+    }
+}
+
+#[derive(Event, Clone, Debug)]
+pub enum DebtEvent {
+    Payment {
+        debt: DebtKind,
+        amount: Money,
+        remaining: Money,
+    },
+    MissedPayment {
+        debt: DebtKind,
+        late_fee: Money,
+        missed_payments: u32,
+    },
+    PaidOff {
+        debt: DebtKind,
+    },
+    Billed {
+        debt: DebtKind,
+        amount: Money,
+    },
+    InterestCharged {
+        debt: DebtKind,
+        amount: Money,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaymentError {
+    NothingOwed,
+    InvalidAmount,
+    InsufficientFunds,
+}
+
+impl std::fmt::Display for PaymentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NothingOwed => write!(f, "nothing is owed on this debt"),
+            Self::InvalidAmount => write!(f, "payments have to be more than nothing"),
+            Self::InsufficientFunds => write!(f, "not enough money in the wallet"),
+        }
+    }
+}
+
+impl std::error::Error for PaymentError {}
+
+/// A single line of debt with its own billing cycle, due every week on `due_day`.
+#[derive(Clone, Debug)]
+pub struct DebtAccount {
+    kind: DebtKind,
+    principal: Money,
+    apr_basis_points: i64,
+    compounding: Compounding,
+    due_day: Day,
+    minimum_payment: Money,
+    late_fee: Money,
+    recurring_bill: Money,
+    minimum_due: Money,
+    paid_this_cycle: Money,
+    missed_payments: u32,
+}
+
+impl DebtAccount {
+    pub fn kind(&self) -> DebtKind {
+        self.kind
+    }
+
+    pub fn principal(&self) -> Money {
+        self.principal
+    }
+
+    pub fn apr_basis_points(&self) -> i64 {
+        self.apr_basis_points
+    }
+
+    pub fn compounding(&self) -> Compounding {
+        self.compounding
+    }
+
+    pub fn due_day(&self) -> Day {
+        self.due_day
+    }
+
+    pub fn late_fee(&self) -> Money {
+        self.late_fee
+    }
+
+    /// What still has to be paid before `due_day` to avoid a late fee
+    pub fn minimum_due(&self) -> Money {
+        self.minimum_due
+            .saturating_sub(self.paid_this_cycle)
+            .max(Money::ZERO)
+    }
+
+    pub fn paid_this_cycle(&self) -> Money {
+        self.paid_this_cycle
+    }
+
+    pub fn missed_payments(&self) -> u32 {
+        self.missed_payments
+    }
+
+    pub fn is_paid_off(&self) -> bool {
+        !self.principal.is_positive()
+    }
+
+    fn on_hour(&mut self, date: &Date) -> Vec<DebtEvent> {
+        let mut events = Vec::new();
+
+        if self.principal.is_positive()
+            && self.apr_basis_points > 0
+            && self.compounding.is_period_end(date)
+        {
+            let rate = Ratio::new(
+                self.apr_basis_points,
+                10_000 * self.compounding.periods_per_year(),
+            );
+            let interest = self.principal.mul_ratio(rate, Rounding::HalfEven);
+            if interest.is_positive() {
+                self.principal += interest;
+                events.push(DebtEvent::InterestCharged {
+                    debt: self.kind,
+                    amount: interest,
+                });
+            }
+        }
+
+        if date.hour == 0 && date.day == self.due_day {
+            if self.minimum_due().is_positive() {
+                self.principal += self.late_fee;
+                self.missed_payments += 1;
+                events.push(DebtEvent::MissedPayment {
+                    debt: self.kind,
+                    late_fee: self.late_fee,
+                    missed_payments: self.missed_payments,
+                });
+            }
+
+            if self.recurring_bill.is_positive() {
+                self.principal += self.recurring_bill;
+                events.push(DebtEvent::Billed {
+                    debt: self.kind,
+                    amount: self.recurring_bill,
+                });
+            }
+
+            self.paid_this_cycle = Money::ZERO;
+            self.minimum_due = self.minimum_payment.min(self.principal.max(Money::ZERO));
+        }
+
+        events
+    }
+
+    fn pay(&mut self, amount: Money) -> Result<(Money, Vec<DebtEvent>), PaymentError> {
+        if self.is_paid_off() {
+            return Err(PaymentError::NothingOwed);
+        }
+        if !amount.is_positive() {
+            return Err(PaymentError::InvalidAmount);
+        }
+
+        let amount = amount.min(self.principal);
+        self.principal -= amount;
+        self.paid_this_cycle += amount;
+
+        let mut events = vec![DebtEvent::Payment {
+            debt: self.kind,
+            amount,
+            remaining: self.principal,
+        }];
+        if self.is_paid_off() {
+            events.push(DebtEvent::PaidOff { debt: self.kind });
+        }
+
+        Ok((amount, events))
+    }
+}
+
 #[derive(Resource)]
 pub struct Debts {
-    medical: Money,
-    rent: Money,
-    utilities: Money,
+    medical: DebtAccount,
+    rent: DebtAccount,
+    utilities: DebtAccount,
 }
 
 impl Debts {
     pub fn total(&self) -> Money {
-        self.medical + self.rent + self.utilities
+        self.accounts().map(|account| account.principal).sum()
     }
 
     pub fn medical(&self) -> Money {
-        self.medical
+        self.medical.principal
     }
 
     pub fn rent(&self) -> Money {
-        self.rent
+        self.rent.principal
     }
 
     pub fn utilities(&self) -> Money {
-        self.utilities
+        self.utilities.principal
+    }
+
+    pub fn account(&self, debt: DebtKind) -> &DebtAccount {
+        match debt {
+            DebtKind::Medical => &self.medical,
+            DebtKind::Rent => &self.rent,
+            DebtKind::Utilities => &self.utilities,
+        }
+    }
+
+    fn account_mut(&mut self, debt: DebtKind) -> &mut DebtAccount {
+        match debt {
+            DebtKind::Medical => &mut self.medical,
+            DebtKind::Rent => &mut self.rent,
+            DebtKind::Utilities => &mut self.utilities,
+        }
+    }
+
+    pub fn accounts(&self) -> impl Iterator<Item = &DebtAccount> {
+        [&self.medical, &self.rent, &self.utilities].into_iter()
+    }
+
+    pub fn missed_payments(&self) -> u32 {
+        self.accounts().map(|account| account.missed_payments).sum()
+    }
+
+    /// Moves `amount` (capped at what's owed) out of the wallet and onto `debt`.
+    pub fn pay(
+        &mut self,
+        debt: DebtKind,
+        amount: Money,
+        wallet: &mut Wallet,
+        date: Date,
+    ) -> Result<(WalletNotification, Vec<DebtEvent>), PaymentError> {
+        let account = self.account_mut(debt);
+        if account.is_paid_off() {
+            return Err(PaymentError::NothingOwed);
+        }
+        if amount.min(account.principal) > wallet.funds() {
+            return Err(PaymentError::InsufficientFunds);
+        }
+
+        let (paid, events) = account.pay(amount)?;
+        let notification = wallet.debit(paid, debt.category(), date, format!("{} payment", debt));
+        Ok((notification, events))
+    }
+
+    fn on_hour(&mut self, date: &Date) -> Vec<DebtEvent> {
+        let mut events = self.medical.on_hour(date);
+        events.extend(self.rent.on_hour(date));
+        events.extend(self.utilities.on_hour(date));
+        events
     }
 }
 
 impl Default for Debts {
     fn default() -> Self {
+        let medical = Money::from_dollars(1_000_000);
+        let medical_minimum = Money::from_dollars(250);
+        let rent = Money::from_dollars(300);
+        let utilities = Money::from_dollars(60);
+
         Debts {
-            medical: 1000000.0.into(),
-            rent: 0.0.into(),
-            utilities: 0.0.into(),
+            medical: DebtAccount {
+                kind: DebtKind::Medical,
+                principal: medical,
+                apr_basis_points: 500,
+                compounding: Compounding::Daily,
+                due_day: Day::Friday,
+                minimum_payment: medical_minimum,
+                late_fee: Money::from_dollars(35),
+                recurring_bill: Money::ZERO,
+                minimum_due: medical_minimum,
+                paid_this_cycle: Money::ZERO,
+                missed_payments: 0,
+            },
+            rent: DebtAccount {
+                kind: DebtKind::Rent,
+                principal: Money::ZERO,
+                apr_basis_points: 0,
+                compounding: Compounding::Weekly,
+                due_day: Day::Sunday,
+                minimum_payment: rent,
+                late_fee: Money::from_dollars(50),
+                recurring_bill: rent,
+                minimum_due: Money::ZERO,
+                paid_this_cycle: Money::ZERO,
+                missed_payments: 0,
+            },
+            utilities: DebtAccount {
+                kind: DebtKind::Utilities,
+                principal: Money::ZERO,
+                apr_basis_points: 0,
+                compounding: Compounding::Weekly,
+                due_day: Day::Wednesday,
+                minimum_payment: utilities,
+                late_fee: Money::from_dollars(10),
+                recurring_bill: utilities,
+                minimum_due: Money::ZERO,
+                paid_this_cycle: Money::ZERO,
+                missed_payments: 0,
+            },
         }
     }
 }
 
-#[derive(Component)]
-pub struct PopUp {
-    timer: Timer,
-    starting_x: f32,
+/// Pays toward a debt from the `Wallet`, posting the usual notification and sending `DebtEvent`s.
+pub struct PayDebtCommand {
+    pub debt: DebtKind,
+    pub amount: Money,
 }
 
-impl PopUp {
-    pub fn new(starting_x: f32) -> Self {
-        PopUp {
-            timer: Timer::from_seconds(2.0, TimerMode::Once),
-            starting_x,
-        }
-    }
+impl Command for PayDebtCommand {
+    fn apply(self, world: &mut World) {
+        let date = world.resource::<Phone>().date;
+        let result = world.resource_scope(|world, mut debts: Mut<Debts>| {
+            let mut wallet = world.resource_mut::<Wallet>();
+            debts.pay(self.debt, self.amount, &mut wallet, date)
+        });
 
-    pub fn tick(&mut self, delta: std::time::Duration) {
-        self.timer.tick(delta);
+        match result {
+            Ok((notification, events)) => {
+                notification.apply(world);
+                world.send_event_batch(events);
+            }
+            Err(error) => error!("Couldn't pay {} debt: {}", self.debt, error),
+        }
     }
+}
 
-    pub fn finished(&self) -> bool {
-        self.timer.finished()
+fn process_debts(
+    mut hour_events: EventReader<HourPassed>,
+    mut debts: ResMut<Debts>,
+    mut debt_events: EventWriter<DebtEvent>,
+) {
+    for HourPassed(date) in hour_events.iter() {
+        debt_events.send_batch(debts.on_hour(date));
     }
 }
 
-pub struct PopUpCommand(pub f32);
+/// Tells the player their wallet changed, replacing the previous unread wallet notification
+pub struct WalletNotification {
+    pub amount: Money,
+    pub memo: String,
+}
 
-impl Command for PopUpCommand {
+impl Command for WalletNotification {
     fn apply(self, world: &mut World) {
-        let popup_color = if self.0 < 0.0 {
+        let locale = world.resource::<MoneyLocale>();
+        let body = format!(
+            "{} {} (balance {})",
+            self.memo,
+            self.amount.localized(locale),
+            world.resource::<Wallet>().funds().localized(locale)
+        );
+        let icon = if self.amount.is_negative() {
             Color::RED
         } else {
             Color::GREEN
         };
 
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
-        let x_pos = rng.gen_range(-200.0..200.0);
-
-        let display_text = if self.0 < 0.0 {
-            format!("{:.2}", self.0)
-        } else {
-            format!("+{:.2}", self.0)
-        };
-
-        world.spawn((
-            Sprite {
-                color: popup_color,
-                custom_size: Some(Vec2::new(100.0, 100.0)),
-                ..default()
-            },
-            Text2dBundle {
-                text: Text {
-                    sections: vec![TextSection {
-                        value: display_text,
-                        style: TextStyle {
-                            font_size: 30.0,
-                            color: Color::WHITE,
-                            ..default()
-                        },
-                    }],
-                    alignment: TextAlignment::Center,
-                    ..default()
-                },
-                transform: Transform::from_xyz(x_pos, -10.0, 0.0),
-                ..default()
-            },
-            PopUp::new(x_pos),
-        ));
+        let source = world
+            .get_resource::<WalletNotificationSource>()
+            .map(|source| source.0);
+        PostNotification(
+            Notification {
+                source,
+                ..Notification::new("Wallet", body)
+            }
+            .with_icon(icon)
+            .with_tag("wallet"),
+        )
+        .apply(world);
     }
 }
 
+/// Name of the phone app that wallet notifications come from, set by whichever app shows the wallet
+#[derive(Resource)]
+pub struct WalletNotificationSource(pub &'static str);
+
 #[derive(Resource)]
 pub struct Wallet {
     funds: Money,
+    ledger: Ledger,
 }
 
 impl Wallet {
@@ -160,31 +477,285 @@ impl Wallet {
         self.funds
     }
 
-    pub fn debit(&mut self, amount: Money) -> PopUpCommand {
+    pub fn ledger(&self) -> &Ledger {
+        &self.ledger
+    }
+
+    pub fn debit(
+        &mut self,
+        amount: Money,
+        category: TransactionCategory,
+        date: Date,
+        memo: impl Into<String>,
+    ) -> WalletNotification {
+        let memo = memo.into();
         self.funds -= amount;
-        PopUpCommand(-amount.0)
+        self.ledger
+            .record(-amount, self.funds, category, date, memo.clone());
+        WalletNotification {
+            amount: -amount,
+            memo,
+        }
     }
 
-    pub fn credit(&mut self, amount: Money) -> PopUpCommand {
+    pub fn credit(
+        &mut self,
+        amount: Money,
+        category: TransactionCategory,
+        date: Date,
+        memo: impl Into<String>,
+    ) -> WalletNotification {
+        let memo = memo.into();
         self.funds += amount;
-        PopUpCommand(amount.0)
+        self.ledger
+            .record(amount, self.funds, category, date, memo.clone());
+        WalletNotification { amount, memo }
     }
 }
 
 impl Default for Wallet {
     fn default() -> Self {
+        let funds = Money::from_cents(2530);
         Wallet {
-            funds: 25.30.into(),
+            funds,
+            ledger: Ledger::new(funds),
         }
     }
 }
 
-#[derive(Copy, Clone, Default)]
-pub struct Money(f32);
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TransactionCategory {
+    Ammo,
+    Rent,
+    Medical,
+    Utilities,
+    Income,
+    Other,
+}
+
+impl std::fmt::Display for TransactionCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let string_representation = match self {
+            Self::Ammo => "Ammo",
+            Self::Rent => "Rent",
+            Self::Medical => "Medical",
+            Self::Utilities => "Utilities",
+            Self::Income => "Income",
+            Self::Other => "Other",
+        };
+        write!(f, "{}", string_representation)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LedgerEntry {
+    /// Negative for money leaving the wallet
+    pub amount: Money,
+    /// Wallet funds right after this entry was applied
+    pub balance: Money,
+    pub category: TransactionCategory,
+    pub date: Date,
+    pub memo: String,
+}
+
+/// Append-only history of every change made to the `Wallet`.
+pub struct Ledger {
+    opening_balance: Money,
+    entries: Vec<LedgerEntry>,
+}
+
+impl Ledger {
+    pub fn new(opening_balance: Money) -> Self {
+        Ledger {
+            opening_balance,
+            entries: Vec::new(),
+        }
+    }
+
+    fn record(
+        &mut self,
+        amount: Money,
+        balance: Money,
+        category: TransactionCategory,
+        date: Date,
+        memo: String,
+    ) {
+        self.entries.push(LedgerEntry {
+            amount,
+            balance,
+            category,
+            date,
+            memo,
+        });
+    }
+
+    pub fn entries(&self) -> &[LedgerEntry] {
+        &self.entries
+    }
+
+    /// The most recent `count` entries, newest first.
+    pub fn latest(&self, count: usize) -> impl Iterator<Item = &LedgerEntry> {
+        self.entries.iter().rev().take(count)
+    }
+
+    /// Wallet funds after every entry stamped at or before `date` was applied.
+    pub fn balance_at(&self, date: Date) -> Money {
+        self.entries
+            .iter()
+            .rev()
+            .find(|entry| entry.date <= date)
+            .map_or(self.opening_balance, |entry| entry.balance)
+    }
+
+    /// Everything that has ever been credited to the wallet
+    pub fn total_earned(&self) -> Money {
+        self.entries
+            .iter()
+            .map(|entry| entry.amount)
+            .filter(Money::is_positive)
+            .sum()
+    }
+
+    pub fn total_for(&self, category: TransactionCategory) -> Money {
+        self.total_where(category, |_| true)
+    }
+
+    pub fn total_for_day(&self, category: TransactionCategory, day_number: u32) -> Money {
+        self.total_where(category, |date| date.day_number == day_number)
+    }
+
+    pub fn total_for_week(&self, category: TransactionCategory, week: u32) -> Money {
+        self.total_where(category, |date| date.week() == week)
+    }
+
+    pub fn totals_for_day(&self, day_number: u32) -> HashMap<TransactionCategory, Money> {
+        self.totals_where(|date| date.day_number == day_number)
+    }
+
+    pub fn totals_for_week(&self, week: u32) -> HashMap<TransactionCategory, Money> {
+        self.totals_where(|date| date.week() == week)
+    }
+
+    fn total_where(
+        &self,
+        category: TransactionCategory,
+        in_range: impl Fn(&Date) -> bool,
+    ) -> Money {
+        self.entries
+            .iter()
+            .filter(|entry| entry.category == category && in_range(&entry.date))
+            .map(|entry| entry.amount)
+            .sum()
+    }
+
+    fn totals_where(
+        &self,
+        in_range: impl Fn(&Date) -> bool,
+    ) -> HashMap<TransactionCategory, Money> {
+        let mut totals = HashMap::new();
+        for entry in self.entries.iter().filter(|entry| in_range(&entry.date)) {
+            *totals.entry(entry.category).or_insert(Money::ZERO) += entry.amount;
+        }
+        totals
+    }
+}
+
+/// An exact amount of money, stored as a signed number of cents.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Money(i64);
+
+impl Money {
+    pub const ZERO: Money = Money(0);
+    pub const MAX: Money = Money(i64::MAX);
+    pub const MIN: Money = Money(i64::MIN);
+
+    pub const fn from_cents(cents: i64) -> Self {
+        Money(cents)
+    }
+
+    pub const fn from_dollars(dollars: i64) -> Self {
+        Money(dollars * 100)
+    }
+
+    pub fn cents(&self) -> i64 {
+        self.0
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.0 < 0
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.0 > 0
+    }
+
+    /// Panics on `Money::MIN`, which has no positive counterpart.
+    pub fn abs(&self) -> Money {
+        self.checked_abs()
+            .expect("Money overflowed taking its absolute value")
+    }
+
+    pub fn checked_abs(&self) -> Option<Money> {
+        self.0.checked_abs().map(Money)
+    }
+
+    pub fn checked_neg(self) -> Option<Money> {
+        self.0.checked_neg().map(Money)
+    }
+
+    pub fn checked_add(self, rhs: Money) -> Option<Money> {
+        self.0.checked_add(rhs.0).map(Money)
+    }
+
+    pub fn checked_sub(self, rhs: Money) -> Option<Money> {
+        self.0.checked_sub(rhs.0).map(Money)
+    }
+
+    pub fn saturating_add(self, rhs: Money) -> Money {
+        Money(self.0.saturating_add(rhs.0))
+    }
+
+    pub fn saturating_sub(self, rhs: Money) -> Money {
+        Money(self.0.saturating_sub(rhs.0))
+    }
 
-impl From<f32> for Money {
-    fn from(value: f32) -> Self {
-        Money((value * 100.0).round() / 100.0)
+    pub fn checked_mul_ratio(self, ratio: Ratio, rounding: Rounding) -> Option<Money> {
+        let product = self.0 as i128 * ratio.numerator as i128;
+        let cents = rounding.divide(product, ratio.denominator as i128);
+        i64::try_from(cents).ok().map(Money)
+    }
+
+    pub fn checked_div_ratio(self, ratio: Ratio, rounding: Rounding) -> Option<Money> {
+        if ratio.numerator == 0 {
+            return None;
+        }
+        let product = self.0 as i128 * ratio.denominator as i128;
+        let cents = rounding.divide(product, ratio.numerator as i128);
+        i64::try_from(cents).ok().map(Money)
+    }
+
+    /// Scales by `ratio`, panicking if the result doesn't fit.
+    pub fn mul_ratio(self, ratio: Ratio, rounding: Rounding) -> Money {
+        self.checked_mul_ratio(ratio, rounding)
+            .expect("Money overflowed while multiplying by a ratio")
+    }
+
+    /// Divides by `ratio`, panicking on a zero ratio or if the result doesn't fit.
+    pub fn div_ratio(self, ratio: Ratio, rounding: Rounding) -> Money {
+        self.checked_div_ratio(ratio, rounding)
+            .expect("Money overflowed or divided by zero while dividing by a ratio")
+    }
+
+    pub fn localized<'a>(&self, locale: &'a MoneyLocale) -> LocalizedMoney<'a> {
+        LocalizedMoney {
+            money: *self,
+            locale,
+        }
     }
 }
 
@@ -197,7 +768,7 @@ impl Add for Money {
 
 impl AddAssign for Money {
     fn add_assign(&mut self, rhs: Self) {
-        *self = Money(self.0 + rhs.0);
+        *self = *self + rhs;
     }
 }
 
@@ -211,19 +782,496 @@ impl Sub for Money {
 
 impl SubAssign for Money {
     fn sub_assign(&mut self, rhs: Self) {
-        *self = Money(self.0 - rhs.0)
+        *self = *self - rhs;
     }
 }
 
-impl Mul<f32> for Money {
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Self::Output {
+        self.checked_neg().expect("Money overflowed while negating")
+    }
+}
+
+impl Mul<i64> for Money {
     type Output = Self;
-    fn mul(self, rhs: f32) -> Self::Output {
+    fn mul(self, rhs: i64) -> Self::Output {
         Money(self.0 * rhs)
     }
 }
 
+impl Mul<Ratio> for Money {
+    type Output = Self;
+    fn mul(self, rhs: Ratio) -> Self::Output {
+        self.mul_ratio(rhs, Rounding::default())
+    }
+}
+
+impl Div<i64> for Money {
+    type Output = Self;
+    fn div(self, rhs: i64) -> Self::Output {
+        self.div_ratio(Ratio::new(rhs, 1), Rounding::default())
+    }
+}
+
+impl Div<Ratio> for Money {
+    type Output = Self;
+    fn div(self, rhs: Ratio) -> Self::Output {
+        self.div_ratio(rhs, Rounding::default())
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Money::ZERO, |total, money| total + money)
+    }
+}
+
+impl<'a> Sum<&'a Money> for Money {
+    fn sum<I: Iterator<Item = &'a Money>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+/// Formats with the default locale, `{:+}` prints a leading `+` on positive amounts.
 impl std::fmt::Display for Money {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "${:.2}", self.0)
+        write!(f, "{}", MoneyLocale::default().format(*self, f.sign_plus()))
+    }
+}
+
+impl TryFrom<String> for Money {
+    type Error = ParseMoneyError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl FromStr for Money {
+    type Err = ParseMoneyError;
+
+    /// Parses amounts like `$1,234.56`, `-$5`, `$-0.5` or `1234.50`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rest = s.trim();
+        let mut negative = false;
+
+        if let Some(stripped) = rest.strip_prefix('-') {
+            negative = true;
+            rest = stripped;
+        } else if let Some(stripped) = rest.strip_prefix('+') {
+            rest = stripped;
+        }
+
+        rest = rest.strip_prefix('$').unwrap_or(rest);
+
+        if !negative {
+            if let Some(stripped) = rest.strip_prefix('-') {
+                negative = true;
+                rest = stripped;
+            }
+        }
+
+        let (whole, fraction) = match rest.split_once('.') {
+            Some((whole, fraction)) => (whole, fraction),
+            None => (rest, ""),
+        };
+
+        if whole.is_empty() && fraction.is_empty() {
+            return Err(ParseMoneyError::Empty);
+        }
+
+        if whole.contains(',') {
+            let mut groups = whole.split(',');
+            let leading = groups.next().unwrap_or_default();
+            if leading.is_empty() || leading.len() > 3 || groups.any(|group| group.len() != 3) {
+                return Err(ParseMoneyError::MisplacedSeparator);
+            }
+        }
+
+        if fraction.len() > 2 {
+            return Err(ParseMoneyError::TooPrecise);
+        }
+
+        let mut dollars: i64 = 0;
+        for character in whole.chars().filter(|c| *c != ',') {
+            let digit = character
+                .to_digit(10)
+                .ok_or(ParseMoneyError::InvalidCharacter(character))?;
+            dollars = dollars
+                .checked_mul(10)
+                .and_then(|value| value.checked_add(digit as i64))
+                .ok_or(ParseMoneyError::Overflow)?;
+        }
+
+        let mut cents: i64 = 0;
+        for (index, character) in fraction.chars().enumerate() {
+            let digit = character
+                .to_digit(10)
+                .ok_or(ParseMoneyError::InvalidCharacter(character))?;
+            cents += digit as i64 * if index == 0 { 10 } else { 1 };
+        }
+
+        let total = dollars
+            .checked_mul(100)
+            .and_then(|value| value.checked_add(cents))
+            .ok_or(ParseMoneyError::Overflow)?;
+
+        Ok(Money(if negative { -total } else { total }))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseMoneyError {
+    Empty,
+    InvalidCharacter(char),
+    MisplacedSeparator,
+    TooPrecise,
+    Overflow,
+}
+
+impl std::fmt::Display for ParseMoneyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "no amount given"),
+            Self::InvalidCharacter(character) => {
+                write!(f, "unexpected character '{}' in amount", character)
+            }
+            Self::MisplacedSeparator => write!(f, "thousands separator in the wrong place"),
+            Self::TooPrecise => write!(f, "amounts can't go below a cent"),
+            Self::Overflow => write!(f, "amount is too large"),
+        }
+    }
+}
+
+impl std::error::Error for ParseMoneyError {}
+
+/// An exact fraction used to scale `Money` without going through floats.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Ratio {
+    numerator: i64,
+    denominator: i64,
+}
+
+impl Ratio {
+    pub const ZERO: Ratio = Ratio {
+        numerator: 0,
+        denominator: 1,
+    };
+
+    pub const ONE: Ratio = Ratio {
+        numerator: 1,
+        denominator: 1,
+    };
+
+    pub fn new(numerator: i64, denominator: i64) -> Self {
+        assert!(denominator != 0, "Ratio denominator can't be zero");
+        if denominator < 0 {
+            Ratio {
+                numerator: numerator
+                    .checked_neg()
+                    .expect("Ratio numerator overflowed moving the sign off the denominator"),
+                denominator: denominator
+                    .checked_neg()
+                    .expect("Ratio denominator can't be i64::MIN"),
+            }
+        } else {
+            Ratio {
+                numerator,
+                denominator,
+            }
+        }
+    }
+
+    /// `basis_points` hundredths of a percent, so 1250 is 12.5%.
+    pub fn from_basis_points(basis_points: i64) -> Self {
+        Ratio::new(basis_points, 10_000)
+    }
+
+    pub fn numerator(&self) -> i64 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> i64 {
+        self.denominator
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numerator == 0
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Rounding {
+    /// Towards negative infinity
+    Floor,
+    /// Towards positive infinity
+    Ceil,
+    TowardZero,
+    /// Nearest cent, ties away from zero
+    HalfUp,
+    /// Nearest cent, ties to the even cent
+    #[default]
+    HalfEven,
+}
+
+impl Rounding {
+    fn divide(&self, numerator: i128, denominator: i128) -> i128 {
+        let (numerator, denominator) = if denominator < 0 {
+            (-numerator, -denominator)
+        } else {
+            (numerator, denominator)
+        };
+
+        let quotient = numerator / denominator;
+        let remainder = numerator % denominator;
+        if remainder == 0 {
+            return quotient;
+        }
+
+        let away_from_zero = if numerator < 0 {
+            quotient - 1
+        } else {
+            quotient + 1
+        };
+
+        match self {
+            Self::TowardZero => quotient,
+            Self::Floor => {
+                if numerator < 0 {
+                    away_from_zero
+                } else {
+                    quotient
+                }
+            }
+            Self::Ceil => {
+                if numerator > 0 {
+                    away_from_zero
+                } else {
+                    quotient
+                }
+            }
+            Self::HalfUp | Self::HalfEven => match (remainder.abs() * 2).cmp(&denominator) {
+                Ordering::Less => quotient,
+                Ordering::Greater => away_from_zero,
+                Ordering::Equal => {
+                    if *self == Self::HalfUp || quotient % 2 != 0 {
+                        away_from_zero
+                    } else {
+                        quotient
+                    }
+                }
+            },
+        }
+    }
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct MoneyLocale {
+    pub symbol: &'static str,
+    pub symbol_position: SymbolPosition,
+    pub thousands_separator: char,
+    pub decimal_separator: char,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SymbolPosition {
+    Prefix,
+    Suffix,
+}
+
+impl MoneyLocale {
+    pub const EN_US: MoneyLocale = MoneyLocale {
+        symbol: "$",
+        symbol_position: SymbolPosition::Prefix,
+        thousands_separator: ',',
+        decimal_separator: '.',
+    };
+
+    pub const DE_DE: MoneyLocale = MoneyLocale {
+        symbol: "$",
+        symbol_position: SymbolPosition::Suffix,
+        thousands_separator: '.',
+        decimal_separator: ',',
+    };
+
+    pub const FR_FR: MoneyLocale = MoneyLocale {
+        symbol: "$",
+        symbol_position: SymbolPosition::Suffix,
+        thousands_separator: '\u{202F}',
+        decimal_separator: ',',
+    };
+
+    pub fn format(&self, money: Money, sign_plus: bool) -> String {
+        let cents = money.0.unsigned_abs();
+        let whole = (cents / 100).to_string();
+        let mut grouped: Vec<char> = Vec::with_capacity(whole.len() + whole.len() / 3);
+        for (index, digit) in whole.chars().rev().enumerate() {
+            if index > 0 && index % 3 == 0 {
+                grouped.push(self.thousands_separator);
+            }
+            grouped.push(digit);
+        }
+        let grouped: String = grouped.into_iter().rev().collect();
+
+        let sign = if money.is_negative() {
+            "-"
+        } else if sign_plus && money.is_positive() {
+            "+"
+        } else {
+            ""
+        };
+
+        match self.symbol_position {
+            SymbolPosition::Prefix => format!(
+                "{}{}{}{}{:0>2}",
+                sign,
+                self.symbol,
+                grouped,
+                self.decimal_separator,
+                cents % 100
+            ),
+            SymbolPosition::Suffix => format!(
+                "{}{}{}{:0>2} {}",
+                sign,
+                grouped,
+                self.decimal_separator,
+                cents % 100,
+                self.symbol
+            ),
+        }
+    }
+}
+
+impl Default for MoneyLocale {
+    fn default() -> Self {
+        MoneyLocale::EN_US
+    }
+}
+
+pub struct LocalizedMoney<'a> {
+    money: Money,
+    locale: &'a MoneyLocale,
+}
+
+impl std::fmt::Display for LocalizedMoney<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.locale.format(self.money, f.sign_plus()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Result<Money, ParseMoneyError> {
+        s.parse()
+    }
+
+    #[test]
+    fn parses_plain_and_grouped_amounts() {
+        assert_eq!(parse("1234.50"), Ok(Money::from_cents(123_450)));
+        assert_eq!(parse("$1,234.56"), Ok(Money::from_cents(123_456)));
+        assert_eq!(parse("12,345,678"), Ok(Money::from_dollars(12_345_678)));
+        assert_eq!(parse(" $0.5 "), Ok(Money::from_cents(50)));
+        assert_eq!(parse("+$.05"), Ok(Money::from_cents(5)));
+    }
+
+    #[test]
+    fn parses_negatives() {
+        assert_eq!(parse("-$5"), Ok(Money::from_dollars(-5)));
+        assert_eq!(parse("$-0.5"), Ok(Money::from_cents(-50)));
+        assert_eq!(parse("-1,000.01"), Ok(Money::from_cents(-100_001)));
+    }
+
+    #[test]
+    fn rejects_bad_amounts() {
+        assert_eq!(parse(""), Err(ParseMoneyError::Empty));
+        assert_eq!(parse("$"), Err(ParseMoneyError::Empty));
+        assert_eq!(parse("1,23"), Err(ParseMoneyError::MisplacedSeparator));
+        assert_eq!(parse(",123"), Err(ParseMoneyError::MisplacedSeparator));
+        assert_eq!(parse("1234,567"), Err(ParseMoneyError::MisplacedSeparator));
+        assert_eq!(parse("1.234"), Err(ParseMoneyError::TooPrecise));
+        assert_eq!(parse("12a"), Err(ParseMoneyError::InvalidCharacter('a')));
+        assert_eq!(parse("--5"), Err(ParseMoneyError::InvalidCharacter('-')));
+        assert_eq!(
+            parse("999999999999999999999"),
+            Err(ParseMoneyError::Overflow)
+        );
+    }
+
+    fn scaled(cents: i64, numerator: i64, denominator: i64, rounding: Rounding) -> i64 {
+        Money::from_cents(cents)
+            .mul_ratio(Ratio::new(numerator, denominator), rounding)
+            .cents()
+    }
+
+    #[test]
+    fn rounds_each_mode() {
+        // (mode, 2.5, -2.5, 3.5, 2.4, -2.6)
+        let cases = [
+            (Rounding::Floor, [2, -3, 3, 2, -3]),
+            (Rounding::Ceil, [3, -2, 4, 3, -2]),
+            (Rounding::TowardZero, [2, -2, 3, 2, -2]),
+            (Rounding::HalfUp, [3, -3, 4, 2, -3]),
+            (Rounding::HalfEven, [2, -2, 4, 2, -3]),
+        ];
+        for (rounding, expected) in cases {
+            let actual = [
+                scaled(5, 1, 2, rounding),
+                scaled(-5, 1, 2, rounding),
+                scaled(7, 1, 2, rounding),
+                scaled(12, 1, 5, rounding),
+                scaled(-13, 1, 5, rounding),
+            ];
+            assert_eq!(actual, expected, "{:?}", rounding);
+        }
+    }
+
+    #[test]
+    fn exact_results_ignore_rounding() {
+        assert_eq!(scaled(300, 1, 3, Rounding::Ceil), 100);
+        assert_eq!(scaled(-300, 1, 3, Rounding::Floor), -100);
+    }
+
+    #[test]
+    fn ratio_moves_sign_to_numerator() {
+        let ratio = Ratio::new(3, -4);
+        assert_eq!((ratio.numerator(), ratio.denominator()), (-3, 4));
+        assert_eq!(
+            Money::from_cents(100).mul_ratio(ratio, Rounding::HalfEven),
+            Money::from_cents(-75)
+        );
+        assert_eq!(
+            Money::from_cents(100).div_ratio(Ratio::new(-1, -2), Rounding::HalfEven),
+            Money::from_cents(200)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn ratio_rejects_min_denominator() {
+        Ratio::new(1, i64::MIN);
+    }
+
+    #[test]
+    fn ratio_overflow_is_checked() {
+        assert_eq!(
+            Money::MAX.checked_mul_ratio(Ratio::new(2, 1), Rounding::Floor),
+            None
+        );
+        assert_eq!(
+            Money::ZERO.checked_div_ratio(Ratio::ZERO, Rounding::Floor),
+            None
+        );
+    }
+
+    #[test]
+    fn negation_is_checked() {
+        assert_eq!(Money::MIN.checked_abs(), None);
+        assert_eq!(Money::MIN.checked_neg(), None);
+        assert_eq!(Money::MAX.checked_neg(), Some(Money::from_cents(-i64::MAX)));
+        assert_eq!(-Money::from_cents(5), Money::from_cents(-5));
     }
 }
@@ -261,10 +261,10 @@ fn handle_wall_detection(
     }
 }
 
-fn disable_physics_simulation(mut rapier_config: ResMut<RapierConfiguration>) {
+pub fn disable_physics_simulation(mut rapier_config: ResMut<RapierConfiguration>) {
     rapier_config.physics_pipeline_active = false;
 }
 
-fn re_enable_physics_simulation(mut rapier_config: ResMut<RapierConfiguration>) {
+pub fn re_enable_physics_simulation(mut rapier_config: ResMut<RapierConfiguration>) {
     rapier_config.physics_pipeline_active = true;
 }
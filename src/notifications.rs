@@ -0,0 +1,386 @@
+use std::collections::VecDeque;
+
+use bevy::{ecs::system::Command, prelude::*, sprite::Anchor};
+
+use crate::{
+    clock::{Date, Phone},
+    phone::{
+        phone_app_open, AddPhoneApp, PhoneApp, PhoneAppInput, PhoneAppOpened, PhoneApps,
+        PhoneBadges, PhoneInput, PhoneNavigation, PhoneState,
+    },
+    GameState,
+};
+
+pub struct NotificationsPlugin;
+
+impl Plugin for NotificationsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_phone_app::<NotificationsApp>()
+            .init_resource::<Notifications>()
+            .init_resource::<NotificationSelection>()
+            .add_systems(
+                Update,
+                (
+                    spawn_notifications_ui,
+                    notifications_input,
+                    read_opened_app_notifications,
+                    update_notification_badges,
+                    update_notifications_ui.run_if(phone_app_open::<NotificationsApp>),
+                    show_toasts,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::RunAndGun)),
+            );
+    }
+}
+
+#[derive(Component, Default)]
+pub struct NotificationsApp;
+
+impl PhoneApp for NotificationsApp {
+    const NAME: &'static str = "Alerts";
+    const ICON_COLOR: Color = Color::rgb(0.8, 0.3, 0.3);
+}
+
+const TOAST_SECONDS: f32 = 3.0;
+const HIGH_PRIORITY_TOAST_SECONDS: f32 = 5.0;
+const VISIBLE_NOTIFICATIONS: usize = 7;
+
+/// Low priority notifications only show up on the phone, everything else also gets a toast
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NotificationPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+#[derive(Clone, Debug)]
+pub struct Notification {
+    pub title: String,
+    pub body: String,
+    pub icon: Color,
+    pub priority: NotificationPriority,
+    /// Name of the phone app that posted this, which gets the badge and is opened from the list
+    pub source: Option<&'static str>,
+    /// Posting again with the same tag replaces the unread notification instead of stacking
+    pub tag: Option<String>,
+    pub date: Date,
+}
+
+impl Notification {
+    pub fn new(title: impl Into<String>, body: impl Into<String>) -> Self {
+        Notification {
+            title: title.into(),
+            body: body.into(),
+            icon: Color::GRAY,
+            priority: NotificationPriority::Normal,
+            source: None,
+            tag: None,
+            date: Date::default(),
+        }
+    }
+
+    pub fn from_app<A: PhoneApp>(mut self) -> Self {
+        self.source = Some(A::NAME);
+        self.icon = A::ICON_COLOR;
+        self
+    }
+
+    pub fn with_icon(mut self, icon: Color) -> Self {
+        self.icon = icon;
+        self
+    }
+
+    pub fn with_priority(mut self, priority: NotificationPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
+}
+
+/// Unread notifications, oldest first, and the toasts still waiting to be shown
+#[derive(Resource, Default)]
+pub struct Notifications {
+    unread: Vec<Notification>,
+    toasts: VecDeque<Notification>,
+}
+
+impl Notifications {
+    pub fn post(&mut self, notification: Notification) {
+        if let Some(tag) = &notification.tag {
+            self.unread.retain(|other| other.tag.as_ref() != Some(tag));
+            self.toasts.retain(|other| other.tag.as_ref() != Some(tag));
+        }
+
+        if notification.priority > NotificationPriority::Low {
+            // Higher priority toasts jump the queue, equal ones wait their turn
+            let index = self
+                .toasts
+                .iter()
+                .position(|other| other.priority < notification.priority)
+                .unwrap_or(self.toasts.len());
+            self.toasts.insert(index, notification.clone());
+        }
+        self.unread.push(notification);
+    }
+
+    pub fn unread(&self) -> &[Notification] {
+        &self.unread
+    }
+
+    pub fn unread_from(&self, app: &str) -> usize {
+        self.unread
+            .iter()
+            .filter(|notification| notification.source == Some(app))
+            .count()
+    }
+
+    pub fn mark_read_from(&mut self, app: &str) {
+        self.unread
+            .retain(|notification| notification.source != Some(app));
+    }
+
+    pub fn dismiss(&mut self, index: usize) -> Option<Notification> {
+        (index < self.unread.len()).then(|| self.unread.remove(index))
+    }
+
+    pub fn clear(&mut self) {
+        self.unread.clear();
+        self.toasts.clear();
+    }
+}
+
+/// Posts a notification stamped with the current in-game time
+pub struct PostNotification(pub Notification);
+
+impl Command for PostNotification {
+    fn apply(mut self, world: &mut World) {
+        self.0.date = world.resource::<Phone>().date;
+        world.resource_mut::<Notifications>().post(self.0);
+    }
+}
+
+#[derive(Resource, Default)]
+struct NotificationSelection(usize);
+
+#[derive(Component)]
+struct NotificationsText;
+
+#[derive(Component)]
+struct Toast(Timer);
+
+fn spawn_notifications_ui(
+    mut commands: Commands,
+    root_query: Query<Entity, Added<NotificationsApp>>,
+) {
+    for root in &root_query {
+        commands.entity(root).with_children(|parent| {
+            parent
+                .spawn(Text2dBundle {
+                    text: Text::from_section(
+                        "",
+                        TextStyle {
+                            font_size: 18.0,
+                            ..default()
+                        },
+                    ),
+                    text_anchor: Anchor::TopLeft,
+                    transform: Transform::from_xyz(-245.0, 85.0, 1.0),
+                    ..default()
+                })
+                .insert(NotificationsText);
+        });
+    }
+}
+
+/// The list shows the newest notification first
+fn newest_first(notifications: &Notifications, selected: usize) -> Option<usize> {
+    notifications.unread().len().checked_sub(selected + 1)
+}
+
+fn notifications_input(
+    mut app_inputs: EventReader<PhoneAppInput>,
+    apps: Res<PhoneApps>,
+    mut notifications: ResMut<Notifications>,
+    mut selection: ResMut<NotificationSelection>,
+    mut navigation: ResMut<PhoneNavigation>,
+    mut opened_events: EventWriter<PhoneAppOpened>,
+) {
+    for input in apps.inputs_for::<NotificationsApp>(&mut app_inputs) {
+        let count = notifications.unread().len();
+        match input {
+            PhoneInput::Up => selection.0 = selection.0.saturating_sub(1),
+            PhoneInput::Down => selection.0 = (selection.0 + 1).min(count.saturating_sub(1)),
+            PhoneInput::Select => {
+                let Some(notification) = newest_first(&notifications, selection.0)
+                    .and_then(|index| notifications.dismiss(index))
+                else {
+                    continue;
+                };
+                if let Some(app) = notification.source.and_then(|name| apps.find(name)) {
+                    navigation.open(app);
+                    opened_events.send(PhoneAppOpened(app));
+                }
+            }
+            PhoneInput::Right => notifications.clear(),
+            _ => {}
+        }
+        selection.0 = selection
+            .0
+            .min(notifications.unread().len().saturating_sub(1));
+    }
+}
+
+fn read_opened_app_notifications(
+    mut opened_events: EventReader<PhoneAppOpened>,
+    apps: Res<PhoneApps>,
+    mut notifications: ResMut<Notifications>,
+) {
+    for PhoneAppOpened(app) in opened_events.iter() {
+        if let Some(info) = apps.get(*app) {
+            if notifications.unread_from(info.name) > 0 {
+                notifications.mark_read_from(info.name);
+            }
+        }
+    }
+}
+
+fn update_notification_badges(
+    notifications: Res<Notifications>,
+    apps: Res<PhoneApps>,
+    mut badges: ResMut<PhoneBadges>,
+) {
+    if !notifications.is_changed() {
+        return;
+    }
+
+    for (id, info) in apps.iter() {
+        let count = if info.name == NotificationsApp::NAME {
+            notifications.unread().len()
+        } else {
+            notifications.unread_from(info.name)
+        };
+        badges.set(id, count as u32);
+    }
+}
+
+fn update_notifications_ui(
+    notifications: Res<Notifications>,
+    selection: Res<NotificationSelection>,
+    mut text_query: Query<&mut Text, With<NotificationsText>>,
+) {
+    if !(notifications.is_changed() || selection.is_changed()) {
+        return;
+    }
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+
+    let mut lines = Vec::new();
+    for (index, notification) in notifications
+        .unread()
+        .iter()
+        .rev()
+        .enumerate()
+        .skip(selection.0.saturating_sub(VISIBLE_NOTIFICATIONS - 1))
+        .take(VISIBLE_NOTIFICATIONS)
+    {
+        let cursor = if index == selection.0 { ">" } else { " " };
+        lines.push(format!(
+            "{} {} {}: {}",
+            cursor, notification.date, notification.title, notification.body
+        ));
+    }
+    if lines.is_empty() {
+        lines.push("No new notifications".to_string());
+    } else {
+        lines.push("[Select] Open  [>] Clear all".to_string());
+    }
+    text.sections[0].value = lines.join("\n");
+}
+
+/// Mirrors notifications on the HUD one at a time while the phone is down
+fn show_toasts(
+    mut commands: Commands,
+    time: Res<Time>,
+    phone_state: Res<State<PhoneState>>,
+    mut notifications: ResMut<Notifications>,
+    mut toast_query: Query<(Entity, &mut Toast)>,
+) {
+    if *phone_state.get() != PhoneState::Lowered {
+        for (entity, _) in &toast_query {
+            commands.entity(entity).despawn_recursive();
+        }
+        // Anything queued can be read on the phone that's already up
+        if !notifications.toasts.is_empty() {
+            notifications.toasts.clear();
+        }
+        return;
+    }
+
+    if let Ok((entity, mut toast)) = toast_query.get_single_mut() {
+        if toast.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+
+    let Some(notification) = notifications.toasts.pop_front() else {
+        return;
+    };
+    let seconds = match notification.priority {
+        NotificationPriority::High => HIGH_PRIORITY_TOAST_SECONDS,
+        _ => TOAST_SECONDS,
+    };
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(20.0),
+                right: Val::Px(20.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                column_gap: Val::Px(10.0),
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+            z_index: ZIndex::Global(50),
+            ..default()
+        })
+        .insert(Toast(Timer::from_seconds(seconds, TimerMode::Once)))
+        .with_children(|parent| {
+            parent.spawn(NodeBundle {
+                style: Style {
+                    width: Val::Px(24.0),
+                    height: Val::Px(24.0),
+                    ..default()
+                },
+                background_color: notification.icon.into(),
+                ..default()
+            });
+            parent.spawn(TextBundle::from_sections([
+                TextSection::new(
+                    format!("{}\n", notification.title),
+                    TextStyle {
+                        font_size: 26.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                TextSection::new(
+                    notification.body,
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::rgb(0.85, 0.85, 0.85),
+                        ..default()
+                    },
+                ),
+            ]));
+        });
+}
@@ -0,0 +1,172 @@
+use std::fmt;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    reflect::{TypePath, TypeUuid},
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+use crate::weapon::TriggerMode;
+
+/// A swappable gun part, loaded from a `.part.ron` file
+#[derive(Deserialize, TypeUuid, TypePath, Clone, Debug)]
+#[uuid = "5d2a8e61-93c4-4f0b-b7e2-1c6f4a9d3e87"]
+pub struct GunPart {
+    pub name: String,
+    pub slot: PartSlot,
+    #[serde(default)]
+    pub modifiers: PartModifiers,
+    /// Gun platforms this part fits, empty fits everything
+    #[serde(default)]
+    pub platforms: Vec<String>,
+    /// What this part is, for other parts to conflict with
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Tags this part can't be installed alongside
+    #[serde(default)]
+    pub conflicts: Vec<String>,
+}
+
+#[derive(Default)]
+pub struct GunPartLoader;
+
+impl AssetLoader for GunPartLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let part = ron::de::from_bytes::<GunPart>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(part));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["part.ron"]
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PartSlot {
+    Muzzle,
+    Receiver,
+    Clip,
+    Trigger,
+}
+
+impl PartSlot {
+    pub const ALL: [PartSlot; 4] = [
+        PartSlot::Muzzle,
+        PartSlot::Receiver,
+        PartSlot::Clip,
+        PartSlot::Trigger,
+    ];
+}
+
+impl fmt::Display for PartSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// Changes a part makes to the gun it's installed on, percentages are added up across parts
+#[derive(Deserialize, Clone, Copy, Debug, Default)]
+#[serde(default)]
+pub struct PartModifiers {
+    pub damage: f32,
+    pub spread: f32,
+    pub range: f32,
+    pub kick: f32,
+    pub force: f32,
+    pub reload_time: f32,
+    pub shot_interval: f32,
+    /// Extra rounds in the clip
+    pub clip_size: i16,
+    /// Adds a fire mode the gun can be switched to
+    pub trigger_mode: Option<TriggerMode>,
+}
+
+impl PartModifiers {
+    pub fn combine(self, other: PartModifiers) -> PartModifiers {
+        PartModifiers {
+            damage: self.damage + other.damage,
+            spread: self.spread + other.spread,
+            range: self.range + other.range,
+            kick: self.kick + other.kick,
+            force: self.force + other.force,
+            reload_time: self.reload_time + other.reload_time,
+            shot_interval: self.shot_interval + other.shot_interval,
+            clip_size: self.clip_size + other.clip_size,
+            trigger_mode: other.trigger_mode.or(self.trigger_mode),
+        }
+    }
+
+    /// Scales `base` by a summed percentage, never below a tenth of it
+    pub fn scale(base: f32, percent: f32) -> f32 {
+        base * (1.0 + percent / 100.0).max(0.1)
+    }
+
+    /// One line per stat this changes, like "+15% damage"
+    pub fn describe(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        let percentages = [
+            (self.damage, "damage"),
+            (self.spread, "spread"),
+            (self.range, "range"),
+            (self.kick, "kick"),
+            (self.force, "force"),
+            (self.reload_time, "reload time"),
+            (self.shot_interval, "time between shots"),
+        ];
+        for (percent, stat) in percentages {
+            if percent != 0.0 {
+                lines.push(format!("{:+}% {}", percent, stat));
+            }
+        }
+        if self.clip_size != 0 {
+            lines.push(format!("{:+} rounds", self.clip_size));
+        }
+        if let Some(mode) = self.trigger_mode {
+            lines.push(format!("adds {} mode", mode));
+        }
+        lines
+    }
+}
+
+/// Why a part can't go on a gun
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Incompatibility {
+    WrongPlatform,
+    Conflicts(String),
+}
+
+impl fmt::Display for Incompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Incompatibility::WrongPlatform => write!(f, "Doesn't fit this gun"),
+            Incompatibility::Conflicts(other) => write!(f, "Conflicts with {}", other),
+        }
+    }
+}
+
+impl GunPart {
+    /// Checks this part against a gun's platform and the parts it already has, ignoring
+    /// whatever it would replace in its own slot
+    pub fn check_fit(&self, platform: &str, installed: &[GunPart]) -> Result<(), Incompatibility> {
+        if !self.platforms.is_empty() && !self.platforms.iter().any(|p| p == platform) {
+            return Err(Incompatibility::WrongPlatform);
+        }
+
+        for other in installed.iter().filter(|other| other.slot != self.slot) {
+            let clashes =
+                |a: &GunPart, b: &GunPart| a.conflicts.iter().any(|tag| b.tags.contains(tag));
+            if clashes(self, other) || clashes(other, self) {
+                return Err(Incompatibility::Conflicts(other.name.clone()));
+            }
+        }
+        Ok(())
+    }
+}
@@ -10,7 +10,16 @@ use bevy::{
     },
 };
 
-use crate::hud::{PhoneDisplay, WalletDisplay};
+use bevy::utils::HashMap;
+use leafwing_input_manager::prelude::*;
+use std::any::TypeId;
+
+use crate::{
+    hud::{PhoneDisplay, WalletDisplay},
+    input::PlayerAction,
+    player::Player,
+    GameState, PlayerSet,
+};
 
 pub struct PhonePlugin;
 
@@ -18,14 +27,283 @@ impl Plugin for PhonePlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<PhoneScreen>()
             .register_type::<PhoneBody>()
-            .add_systems(OnEnter(crate::GameState::RunAndGun), setup_phone)
+            .add_state::<PhoneState>()
+            .add_event::<PhoneAppOpened>()
+            .add_event::<PhoneInput>()
+            .add_event::<PhoneAppInput>()
+            .init_resource::<PhoneApps>()
+            .init_resource::<PhoneRaise>()
+            .init_resource::<PhoneBadges>()
+            .configure_set(Update, PlayerSet::Combat.run_if(phone_lowered))
+            .init_resource::<PhoneNavigation>()
+            .add_systems(
+                OnEnter(GameState::RunAndGun),
+                (setup_phone, spawn_home_screen),
+            )
             .add_systems(
                 Update,
-                setup_phone_components.run_if(in_state(crate::GameState::RunAndGun)),
+                (
+                    setup_phone_components,
+                    toggle_phone,
+                    animate_phone,
+                    route_phone_input.run_if(in_state(PhoneState::Raised)),
+                    navigate_phone,
+                    show_current_phone_screen,
+                    highlight_selected_icon,
+                    update_badges,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::RunAndGun)),
             );
     }
 }
 
+/// Screen size of the phone's render target, in pixels
+pub const PHONE_SCREEN_SIZE: Vec2 = Vec2::new(512.0, 256.0);
+
+const HOME_COLUMNS: usize = 4;
+const ICON_SIZE: f32 = 64.0;
+const ICON_SPACING: Vec2 = Vec2::new(110.0, 100.0);
+
+const RAISE_SECONDS: f32 = 0.35;
+const PHONE_LIGHT_OFFSET: Vec3 = Vec3::new(0.0, 0.0, -0.6);
+const MOUSE_STEP: f32 = 40.0;
+const STICK_THRESHOLD: f32 = 0.6;
+
+/// Where the phone is. Anything but `Lowered` has the phone in focus, taking mouse and
+/// gamepad input away from the camera and gun
+#[derive(States, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PhoneState {
+    #[default]
+    Lowered,
+    Raising,
+    Raised,
+    Lowering,
+}
+
+impl PhoneState {
+    pub fn is_focused(&self) -> bool {
+        *self != PhoneState::Lowered
+    }
+}
+
+/// Run condition for systems that should stop while the phone is up
+pub fn phone_lowered(phone_state: Res<State<PhoneState>>) -> bool {
+    !phone_state.get().is_focused()
+}
+
+/// Input routed to the phone UI while it is raised
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PhoneInput {
+    Up,
+    Down,
+    Left,
+    Right,
+    Select,
+    Back,
+    Home,
+}
+
+/// A [`PhoneInput`] meant for whichever app was on screen when it was pressed
+#[derive(Event, Clone, Copy, Debug)]
+pub struct PhoneAppInput {
+    pub app: PhoneAppId,
+    pub input: PhoneInput,
+}
+
+fn lowered_pose() -> Transform {
+    Transform::from_xyz(2.8, 1.6, 1.4).with_rotation(Quat::from_axis_angle(Vec3::Y, 0.2))
+}
+
+fn raised_pose() -> Transform {
+    Transform::from_xyz(0.0, 0.0, -0.5)
+}
+
+#[derive(Component)]
+pub struct PhoneModel;
+
+#[derive(Component)]
+pub struct PhoneLight;
+
+/// How far the phone is between lowered (0.0) and raised (1.0)
+#[derive(Resource, Default)]
+pub struct PhoneRaise(f32);
+
+impl PhoneRaise {
+    pub fn amount(&self) -> f32 {
+        self.0
+    }
+}
+
+#[derive(Default)]
+struct PhoneCursor {
+    mouse: Vec2,
+    stick_held: bool,
+}
+
+/// Marker component for a phone app's root entity. Registered with [`AddPhoneApp::add_phone_app`],
+/// the app's UI should be spawned as children of that root (e.g. from an `Added<A>` query)
+pub trait PhoneApp: Component + Default {
+    const NAME: &'static str;
+    const ICON_COLOR: Color;
+}
+
+pub trait AddPhoneApp {
+    fn add_phone_app<A: PhoneApp>(&mut self) -> &mut Self;
+}
+
+impl AddPhoneApp for App {
+    fn add_phone_app<A: PhoneApp>(&mut self) -> &mut Self {
+        self.init_resource::<PhoneApps>();
+        self.world.resource_mut::<PhoneApps>().register::<A>();
+        self.add_systems(OnEnter(GameState::RunAndGun), spawn_phone_app_root::<A>)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PhoneAppId(usize);
+
+pub struct PhoneAppInfo {
+    pub name: &'static str,
+    pub icon_color: Color,
+    type_id: TypeId,
+}
+
+/// Every registered app, in home screen order
+#[derive(Resource, Default)]
+pub struct PhoneApps {
+    apps: Vec<PhoneAppInfo>,
+}
+
+impl PhoneApps {
+    fn register<A: PhoneApp>(&mut self) -> PhoneAppId {
+        if let Some(id) = self.id_of::<A>() {
+            return id;
+        }
+        self.apps.push(PhoneAppInfo {
+            name: A::NAME,
+            icon_color: A::ICON_COLOR,
+            type_id: TypeId::of::<A>(),
+        });
+        PhoneAppId(self.apps.len() - 1)
+    }
+
+    /// The inputs in `events` that were aimed at `A`
+    pub fn inputs_for<'a, A: PhoneApp>(
+        &self,
+        events: &'a mut EventReader<PhoneAppInput>,
+    ) -> impl Iterator<Item = PhoneInput> + 'a {
+        let id = self.id_of::<A>();
+        events
+            .iter()
+            .filter(move |event| Some(event.app) == id)
+            .map(|event| event.input)
+    }
+
+    pub fn id_of<A: PhoneApp>(&self) -> Option<PhoneAppId> {
+        self.apps
+            .iter()
+            .position(|info| info.type_id == TypeId::of::<A>())
+            .map(PhoneAppId)
+    }
+
+    pub fn find(&self, name: &str) -> Option<PhoneAppId> {
+        self.apps
+            .iter()
+            .position(|info| info.name == name)
+            .map(PhoneAppId)
+    }
+
+    pub fn get(&self, id: PhoneAppId) -> Option<&PhoneAppInfo> {
+        self.apps.get(id.0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (PhoneAppId, &PhoneAppInfo)> {
+        self.apps
+            .iter()
+            .enumerate()
+            .map(|(index, info)| (PhoneAppId(index), info))
+    }
+
+    pub fn len(&self) -> usize {
+        self.apps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.apps.is_empty()
+    }
+}
+
+/// Which screen the phone is showing. An empty history is the home screen
+#[derive(Resource, Default, Debug)]
+pub struct PhoneNavigation {
+    history: Vec<PhoneAppId>,
+    selected: usize,
+}
+
+impl PhoneNavigation {
+    pub fn current(&self) -> Option<PhoneAppId> {
+        self.history.last().copied()
+    }
+
+    pub fn is_home(&self) -> bool {
+        self.history.is_empty()
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn open(&mut self, app: PhoneAppId) {
+        if self.current() != Some(app) {
+            self.history.push(app);
+        }
+    }
+
+    pub fn back(&mut self) {
+        self.history.pop();
+    }
+
+    pub fn home(&mut self) {
+        self.history.clear();
+    }
+}
+
+/// Sent whenever an app comes to the front of the phone
+#[derive(Event)]
+pub struct PhoneAppOpened(pub PhoneAppId);
+
+/// Run condition for systems that should only run while `A` is on screen
+pub fn phone_app_open<A: PhoneApp>(navigation: Res<PhoneNavigation>, apps: Res<PhoneApps>) -> bool {
+    navigation.current().is_some() && navigation.current() == apps.id_of::<A>()
+}
+
+#[derive(Component)]
+pub struct PhoneHomeScreen;
+
+#[derive(Component)]
+pub struct PhoneAppRoot(pub PhoneAppId);
+
+#[derive(Component)]
+pub struct PhoneAppIcon(pub PhoneAppId);
+
+#[derive(Component)]
+pub struct PhoneAppBadge(pub PhoneAppId);
+
+/// Counts shown on top of each app's home screen icon, zero hides the badge
+#[derive(Resource, Default)]
+pub struct PhoneBadges(HashMap<PhoneAppId, u32>);
+
+impl PhoneBadges {
+    pub fn get(&self, app: PhoneAppId) -> u32 {
+        self.0.get(&app).copied().unwrap_or_default()
+    }
+
+    pub fn set(&mut self, app: PhoneAppId, count: u32) {
+        self.0.insert(app, count);
+    }
+}
+
 #[derive(Component, Reflect, Default, Debug)]
 #[reflect(Component)]
 pub struct PhoneScreen;
@@ -116,17 +394,18 @@ fn setup_phone(
     ));
 
     //Phone Body
-    commands.spawn(SceneBundle {
-        scene: asset_cache.phone.clone(),
-        transform: Transform::from_xyz(2.8, 1.6, 1.4)
-            .with_rotation(Quat::from_axis_angle(Vec3::Y, 0.2)),
-        ..default()
-    });
+    commands
+        .spawn(SceneBundle {
+            scene: asset_cache.phone.clone(),
+            transform: lowered_pose(),
+            ..default()
+        })
+        .insert(PhoneModel);
 
     // Light: They are currently shared between passes
     commands
         .spawn(PointLightBundle {
-            transform: Transform::from_translation(Vec3::new(2.8, 1.6, 0.8)),
+            transform: Transform::from_translation(lowered_pose().translation + PHONE_LIGHT_OFFSET),
             point_light: PointLight {
                 intensity: 28.5,
                 range: 1.5,
@@ -134,7 +413,8 @@ fn setup_phone(
             },
             ..default()
         })
-        .insert(RenderLayers::layer(1));
+        .insert(RenderLayers::layer(1))
+        .insert(PhoneLight);
 
     // Phone UI Elements
 
@@ -156,14 +436,14 @@ fn setup_phone(
                     TextSection::new(
                         "Dosh: ",
                         TextStyle {
-                            font_size: 48.0,
+                            font_size: 28.0,
                             ..default()
                         },
                     ),
                     TextSection::new(
                         "$0.00",
                         TextStyle {
-                            font_size: 48.0,
+                            font_size: 28.0,
                             ..default()
                         },
                     ),
@@ -171,7 +451,7 @@ fn setup_phone(
                 alignment: TextAlignment::Center,
                 ..default()
             },
-            transform: Transform::from_xyz(0.0, -1.2, 0.0),
+            transform: Transform::from_xyz(150.0, 108.0, 1.0),
             ..default()
         })
         .insert(WalletDisplay);
@@ -182,14 +462,14 @@ fn setup_phone(
                 sections: vec![TextSection::new(
                     "",
                     TextStyle {
-                        font_size: 48.0,
+                        font_size: 28.0,
                         ..default()
                     },
                 )],
                 alignment: TextAlignment::Center,
                 ..default()
             },
-            transform: Transform::from_xyz(0.0, 43.5, 0.0),
+            transform: Transform::from_xyz(-150.0, 108.0, 1.0),
             ..default()
         })
         .insert(PhoneDisplay);
@@ -227,3 +507,291 @@ fn setup_phone_components(
         }
     }
 }
+
+/// Position of the `index`th icon on the home screen grid
+fn icon_position(index: usize) -> Vec2 {
+    let column = (index % HOME_COLUMNS) as f32;
+    let row = (index / HOME_COLUMNS) as f32;
+    let left = -(HOME_COLUMNS as f32 - 1.0) * ICON_SPACING.x / 2.0;
+    Vec2::new(left + column * ICON_SPACING.x, 30.0 - row * ICON_SPACING.y)
+}
+
+fn spawn_home_screen(mut commands: Commands, apps: Res<PhoneApps>) {
+    commands
+        .spawn(SpatialBundle::default())
+        .insert(PhoneHomeScreen)
+        .insert(Name::new("Phone Home Screen"))
+        .with_children(|parent| {
+            for (index, (id, info)) in apps.iter().enumerate() {
+                let position = icon_position(index);
+                parent
+                    .spawn(SpriteBundle {
+                        sprite: Sprite {
+                            color: info.icon_color,
+                            custom_size: Some(Vec2::splat(ICON_SIZE)),
+                            ..default()
+                        },
+                        transform: Transform::from_translation(position.extend(1.0)),
+                        ..default()
+                    })
+                    .insert(PhoneAppIcon(id))
+                    .with_children(|icon| {
+                        icon.spawn(Text2dBundle {
+                            text: Text::from_section(
+                                info.name,
+                                TextStyle {
+                                    font_size: 20.0,
+                                    ..default()
+                                },
+                            )
+                            .with_alignment(TextAlignment::Center),
+                            transform: Transform::from_xyz(0.0, -ICON_SIZE * 0.7, 0.1),
+                            ..default()
+                        });
+                        icon.spawn(Text2dBundle {
+                            text: Text::from_section(
+                                "",
+                                TextStyle {
+                                    font_size: 22.0,
+                                    color: Color::RED,
+                                    ..default()
+                                },
+                            ),
+                            transform: Transform::from_xyz(ICON_SIZE * 0.45, ICON_SIZE * 0.45, 0.2),
+                            ..default()
+                        })
+                        .insert(PhoneAppBadge(id));
+                    });
+            }
+        });
+}
+
+fn spawn_phone_app_root<A: PhoneApp>(mut commands: Commands, apps: Res<PhoneApps>) {
+    if let Some(id) = apps.id_of::<A>() {
+        commands
+            .spawn(SpatialBundle {
+                visibility: Visibility::Hidden,
+                ..default()
+            })
+            .insert(PhoneAppRoot(id))
+            .insert(A::default())
+            .insert(Name::new(A::NAME));
+    }
+}
+
+fn toggle_phone(
+    player_query: Query<&ActionState<PlayerAction>, With<Player>>,
+    phone_state: Res<State<PhoneState>>,
+    mut next_phone_state: ResMut<NextState<PhoneState>>,
+) {
+    if let Ok(action_state) = player_query.get_single() {
+        if action_state.just_pressed(PlayerAction::TogglePhone) {
+            next_phone_state.set(match phone_state.get() {
+                PhoneState::Lowered | PhoneState::Lowering => PhoneState::Raising,
+                PhoneState::Raised | PhoneState::Raising => PhoneState::Lowering,
+            });
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn animate_phone(
+    time: Res<Time>,
+    phone_state: Res<State<PhoneState>>,
+    mut next_phone_state: ResMut<NextState<PhoneState>>,
+    mut raise: ResMut<PhoneRaise>,
+    mut model_query: Query<&mut Transform, (With<PhoneModel>, Without<PhoneLight>)>,
+    mut light_query: Query<&mut Transform, (With<PhoneLight>, Without<PhoneModel>)>,
+) {
+    let step = time.delta_seconds() / RAISE_SECONDS;
+    match phone_state.get() {
+        PhoneState::Raising => {
+            raise.0 = (raise.0 + step).min(1.0);
+            if raise.0 >= 1.0 {
+                next_phone_state.set(PhoneState::Raised);
+            }
+        }
+        PhoneState::Lowering => {
+            raise.0 = (raise.0 - step).max(0.0);
+            if raise.0 <= 0.0 {
+                next_phone_state.set(PhoneState::Lowered);
+            }
+        }
+        _ => return,
+    }
+
+    // Smoothstep so the phone eases in and out of both poses
+    let t = raise.0 * raise.0 * (3.0 - 2.0 * raise.0);
+    let (lowered, raised) = (lowered_pose(), raised_pose());
+    let pose = Transform {
+        translation: lowered.translation.lerp(raised.translation, t),
+        rotation: lowered.rotation.slerp(raised.rotation, t),
+        scale: lowered.scale.lerp(raised.scale, t),
+    };
+
+    for mut transform in &mut model_query {
+        *transform = pose;
+    }
+    for mut transform in &mut light_query {
+        transform.translation = pose.translation + PHONE_LIGHT_OFFSET;
+    }
+}
+
+/// Turns keys, mouse motion and gamepad sticks into discrete [`PhoneInput`]s
+fn route_phone_input(
+    player_query: Query<&ActionState<PlayerAction>, With<Player>>,
+    mut phone_inputs: EventWriter<PhoneInput>,
+    mut cursor: Local<PhoneCursor>,
+) {
+    let Ok(action_state) = player_query.get_single() else {
+        return;
+    };
+
+    for (action, input) in [
+        (PlayerAction::PhoneUp, PhoneInput::Up),
+        (PlayerAction::PhoneDown, PhoneInput::Down),
+        (PlayerAction::PhoneLeft, PhoneInput::Left),
+        (PlayerAction::PhoneRight, PhoneInput::Right),
+        (PlayerAction::PhoneSelect, PhoneInput::Select),
+        (PlayerAction::PhoneBack, PhoneInput::Back),
+        (PlayerAction::PhoneHome, PhoneInput::Home),
+    ] {
+        if action_state.just_pressed(action) {
+            phone_inputs.send(input);
+        }
+    }
+
+    // Mouse motion is accumulated until it has moved far enough to count as a step
+    if let Some(pan) = action_state.axis_pair(PlayerAction::Pan) {
+        cursor.mouse += pan.xy();
+        if cursor.mouse.x.abs() > MOUSE_STEP {
+            phone_inputs.send(if cursor.mouse.x > 0.0 {
+                PhoneInput::Right
+            } else {
+                PhoneInput::Left
+            });
+            cursor.mouse = Vec2::ZERO;
+        } else if cursor.mouse.y.abs() > MOUSE_STEP {
+            phone_inputs.send(if cursor.mouse.y > 0.0 {
+                PhoneInput::Down
+            } else {
+                PhoneInput::Up
+            });
+            cursor.mouse = Vec2::ZERO;
+        }
+    }
+
+    // Sticks step once per push
+    let stick = action_state
+        .axis_pair(PlayerAction::PanGamepad)
+        .map(|axis| axis.xy())
+        .unwrap_or_default();
+    if stick.length() < STICK_THRESHOLD {
+        cursor.stick_held = false;
+    } else if !cursor.stick_held {
+        cursor.stick_held = true;
+        phone_inputs.send(if stick.x.abs() > stick.y.abs() {
+            if stick.x > 0.0 {
+                PhoneInput::Right
+            } else {
+                PhoneInput::Left
+            }
+        } else if stick.y > 0.0 {
+            PhoneInput::Up
+        } else {
+            PhoneInput::Down
+        });
+    }
+}
+
+fn navigate_phone(
+    mut phone_inputs: EventReader<PhoneInput>,
+    apps: Res<PhoneApps>,
+    mut navigation: ResMut<PhoneNavigation>,
+    mut opened_events: EventWriter<PhoneAppOpened>,
+    mut app_inputs: EventWriter<PhoneAppInput>,
+) {
+    for input in phone_inputs.iter() {
+        match input {
+            PhoneInput::Home => navigation.home(),
+            PhoneInput::Back => {
+                navigation.back();
+                if let Some(app) = navigation.current() {
+                    opened_events.send(PhoneAppOpened(app));
+                }
+            }
+            _ if navigation.is_home() && !apps.is_empty() => {
+                let last = apps.len() - 1;
+                let selected = navigation.selected;
+                match input {
+                    PhoneInput::Left => navigation.selected = selected.saturating_sub(1),
+                    PhoneInput::Right => navigation.selected = (selected + 1).min(last),
+                    PhoneInput::Up => navigation.selected = selected.saturating_sub(HOME_COLUMNS),
+                    PhoneInput::Down => navigation.selected = (selected + HOME_COLUMNS).min(last),
+                    PhoneInput::Select => {
+                        let app = PhoneAppId(selected);
+                        navigation.open(app);
+                        opened_events.send(PhoneAppOpened(app));
+                    }
+                    _ => {}
+                }
+            }
+            _ => {
+                if let Some(app) = navigation.current() {
+                    app_inputs.send(PhoneAppInput { app, input: *input });
+                }
+            }
+        }
+    }
+}
+
+fn show_current_phone_screen(
+    navigation: Res<PhoneNavigation>,
+    mut home_query: Query<&mut Visibility, (With<PhoneHomeScreen>, Without<PhoneAppRoot>)>,
+    mut app_query: Query<(&mut Visibility, &PhoneAppRoot), Without<PhoneHomeScreen>>,
+) {
+    if !navigation.is_changed() {
+        return;
+    }
+
+    let visible = |shown: bool| {
+        if shown {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        }
+    };
+
+    for mut visibility in &mut home_query {
+        *visibility = visible(navigation.is_home());
+    }
+    for (mut visibility, root) in &mut app_query {
+        *visibility = visible(navigation.current() == Some(root.0));
+    }
+}
+
+fn highlight_selected_icon(
+    navigation: Res<PhoneNavigation>,
+    mut icon_query: Query<(&mut Transform, &PhoneAppIcon)>,
+) {
+    if navigation.is_changed() {
+        for (mut transform, icon) in &mut icon_query {
+            transform.scale = if icon.0 .0 == navigation.selected {
+                Vec3::splat(1.2)
+            } else {
+                Vec3::ONE
+            };
+        }
+    }
+}
+
+fn update_badges(badges: Res<PhoneBadges>, mut badge_query: Query<(&mut Text, &PhoneAppBadge)>) {
+    if badges.is_changed() {
+        for (mut text, badge) in &mut badge_query {
+            text.sections[0].value = match badges.get(badge.0) {
+                0 => String::new(),
+                count => count.to_string(),
+            };
+        }
+    }
+}
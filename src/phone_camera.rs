@@ -0,0 +1,628 @@
+use std::{
+    path::PathBuf,
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        Mutex,
+    },
+};
+
+use bevy::{
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        extract_resource::{ExtractResource, ExtractResourcePlugin},
+        render_asset::RenderAssets,
+        render_resource::{
+            BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Extent3d, ImageCopyBuffer,
+            ImageDataLayout, MapMode, TextureDescriptor, TextureDimension, TextureFormat,
+            TextureUsages,
+        },
+        renderer::{RenderDevice, RenderQueue},
+        view::RenderLayers,
+        Render, RenderApp, RenderSet,
+    },
+    tasks::AsyncComputeTaskPool,
+};
+
+use bevy_rapier3d::prelude::{QueryFilter, RapierContext};
+use wgpu::Maintain;
+
+use crate::{
+    camera::PrimaryCamera,
+    clock::{Date, Phone},
+    notifications::{Notification, PostNotification},
+    phone::{
+        phone_app_open, AddPhoneApp, PhoneApp, PhoneAppInput, PhoneApps, PhoneInput, PhoneState,
+    },
+    player::Player,
+    GameState,
+};
+
+pub struct PhoneCameraPlugin;
+
+impl Plugin for PhoneCameraPlugin {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = channel();
+
+        app.add_phone_app::<PhoneCameraApp>()
+            .register_type::<PhotoSubject>()
+            .add_event::<PhotoTaken>()
+            .add_event::<PhotoObjectiveCompleted>()
+            .init_resource::<Gallery>()
+            .init_resource::<PhotoRequests>()
+            .init_resource::<PhotoObjectives>()
+            .init_resource::<CameraView>()
+            .insert_resource(PhotoReceiver(Mutex::new(receiver)))
+            .add_plugins(ExtractResourcePlugin::<PhotoRequests>::default())
+            .add_systems(OnEnter(GameState::RunAndGun), spawn_viewfinder)
+            .add_systems(First, clear_photo_requests)
+            .add_systems(
+                Update,
+                (
+                    spawn_camera_app_ui,
+                    follow_primary_camera,
+                    camera_app_input,
+                    receive_photos,
+                    check_photo_objectives,
+                    update_camera_app_ui.run_if(phone_app_open::<PhoneCameraApp>),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::RunAndGun)),
+            );
+
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .insert_resource(PhotoSender(Mutex::new(sender)))
+                .add_systems(
+                    Render,
+                    copy_photos
+                        .after(RenderSet::Render)
+                        .before(RenderSet::Cleanup),
+                );
+        }
+    }
+}
+
+#[derive(Component, Default)]
+pub struct PhoneCameraApp;
+
+impl PhoneApp for PhoneCameraApp {
+    const NAME: &'static str = "Camera";
+    const ICON_COLOR: Color = Color::rgb(0.85, 0.75, 0.2);
+}
+
+const VIEWFINDER_SIZE: Extent3d = Extent3d {
+    width: 320,
+    height: 180,
+    depth_or_array_layers: 1,
+};
+const MAX_PHOTO_DISTANCE: f32 = 150.0;
+const SCREENSHOT_DIRECTORY: &str = "screenshots";
+
+/// Tags an entity so photos can tell it was in frame, e.g. for photo objectives
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+pub struct PhotoSubject(pub String);
+
+pub struct Photo {
+    pub id: u32,
+    pub image: Handle<Image>,
+    pub path: PathBuf,
+    pub date: Date,
+    pub subjects: Vec<String>,
+}
+
+/// Every photo taken this run, oldest first
+#[derive(Resource, Default)]
+pub struct Gallery {
+    photos: Vec<Photo>,
+    pending: Vec<PhotoTaken>,
+    next_id: u32,
+}
+
+impl Gallery {
+    pub fn photos(&self) -> &[Photo] {
+        &self.photos
+    }
+}
+
+/// Sent as soon as the shutter is pressed, before the image has been read back
+#[derive(Event, Clone, Debug)]
+pub struct PhotoTaken {
+    pub id: u32,
+    pub date: Date,
+    pub subjects: Vec<String>,
+}
+
+pub struct PhotoObjective {
+    pub description: String,
+    pub subject: String,
+    /// Only counts between these hours, wrapping past midnight
+    pub hours: Option<(u8, u8)>,
+    pub completed: bool,
+}
+
+impl PhotoObjective {
+    pub fn is_met_by(&self, photo: &PhotoTaken) -> bool {
+        let in_hours = match self.hours {
+            Some((from, to)) if from <= to => (from..to).contains(&photo.date.hour),
+            Some((from, to)) => photo.date.hour >= from || photo.date.hour < to,
+            None => true,
+        };
+        in_hours && photo.subjects.contains(&self.subject)
+    }
+}
+
+#[derive(Resource)]
+pub struct PhotoObjectives(pub Vec<PhotoObjective>);
+
+impl Default for PhotoObjectives {
+    fn default() -> Self {
+        PhotoObjectives(vec![PhotoObjective {
+            description: "Photograph the teahouse at night".to_string(),
+            subject: "Teahouse".to_string(),
+            hours: Some((20, 5)),
+            completed: false,
+        }])
+    }
+}
+
+#[derive(Event, Clone, Debug)]
+pub struct PhotoObjectiveCompleted {
+    pub description: String,
+}
+
+/// Captures the render world should read back this frame
+#[derive(Resource, Clone, Default, ExtractResource)]
+struct PhotoRequests {
+    viewfinder: Handle<Image>,
+    pending: Vec<u32>,
+}
+
+struct CapturedPhoto {
+    id: u32,
+    data: Vec<u8>,
+}
+
+#[derive(Resource)]
+struct PhotoSender(Mutex<Sender<CapturedPhoto>>);
+
+#[derive(Resource)]
+struct PhotoReceiver(Mutex<Receiver<CapturedPhoto>>);
+
+#[derive(Resource, Default)]
+struct CameraView {
+    /// Index of the photo being looked at, or the live viewfinder when `None`
+    gallery: Option<usize>,
+}
+
+#[derive(Component)]
+struct ViewfinderCamera;
+
+#[derive(Component)]
+struct CameraAppScreen;
+
+#[derive(Component)]
+struct CameraAppText;
+
+fn spawn_viewfinder(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut requests: ResMut<PhotoRequests>,
+) {
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: Some("viewfinder"),
+            size: VIEWFINDER_SIZE,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::COPY_SRC
+                | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..default()
+    };
+    image.resize(VIEWFINDER_SIZE);
+    let image_handle = images.add(image);
+    requests.viewfinder = image_handle.clone();
+
+    commands.spawn((
+        Camera3dBundle {
+            camera: Camera {
+                order: -1,
+                target: RenderTarget::Image(image_handle),
+                is_active: false,
+                ..default()
+            },
+            ..default()
+        },
+        FogSettings::default(),
+        RenderLayers::layer(0),
+        ViewfinderCamera,
+        Name::new("Viewfinder Camera"),
+    ));
+}
+
+fn spawn_camera_app_ui(
+    mut commands: Commands,
+    root_query: Query<Entity, Added<PhoneCameraApp>>,
+    requests: Res<PhotoRequests>,
+) {
+    for root in &root_query {
+        commands.entity(root).with_children(|parent| {
+            parent
+                .spawn(SpriteBundle {
+                    sprite: Sprite {
+                        custom_size: Some(Vec2::new(288.0, 162.0)),
+                        ..default()
+                    },
+                    texture: requests.viewfinder.clone(),
+                    transform: Transform::from_xyz(-90.0, -10.0, 1.0),
+                    ..default()
+                })
+                .insert(CameraAppScreen);
+            parent
+                .spawn(Text2dBundle {
+                    text: Text::from_section(
+                        "",
+                        TextStyle {
+                            font_size: 18.0,
+                            ..default()
+                        },
+                    ),
+                    text_anchor: bevy::sprite::Anchor::TopLeft,
+                    transform: Transform::from_xyz(65.0, 70.0, 1.0),
+                    ..default()
+                })
+                .insert(CameraAppText);
+        });
+    }
+}
+
+/// Keeps the viewfinder looking where the player is, but only renders while the app is up
+#[allow(clippy::type_complexity)]
+fn follow_primary_camera(
+    navigation: Res<crate::phone::PhoneNavigation>,
+    apps: Res<PhoneApps>,
+    phone_state: Res<State<PhoneState>>,
+    view: Res<CameraView>,
+    primary_query: Query<
+        (&Transform, &FogSettings),
+        (With<PrimaryCamera>, Without<ViewfinderCamera>),
+    >,
+    mut viewfinder_query: Query<
+        (&mut Camera, &mut Transform, &mut FogSettings),
+        With<ViewfinderCamera>,
+    >,
+) {
+    let active = phone_state.get().is_focused()
+        && navigation.current().is_some()
+        && navigation.current() == apps.id_of::<PhoneCameraApp>()
+        && view.gallery.is_none();
+
+    if let Ok((mut camera, mut transform, mut fog)) = viewfinder_query.get_single_mut() {
+        if camera.is_active != active {
+            camera.is_active = active;
+        }
+        if let (true, Ok((primary_transform, primary_fog))) = (active, primary_query.get_single()) {
+            *transform = *primary_transform;
+            *fog = primary_fog.clone();
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn camera_app_input(
+    mut app_inputs: EventReader<PhoneAppInput>,
+    apps: Res<PhoneApps>,
+    phone: Res<Phone>,
+    mut view: ResMut<CameraView>,
+    mut gallery: ResMut<Gallery>,
+    mut requests: ResMut<PhotoRequests>,
+    mut photo_events: EventWriter<PhotoTaken>,
+    viewfinder_query: Query<(&Camera, &GlobalTransform), With<ViewfinderCamera>>,
+    subject_query: Query<(Entity, &PhotoSubject, &GlobalTransform)>,
+    player_query: Query<Entity, With<Player>>,
+    parent_query: Query<&Parent>,
+    rapier_context: Res<RapierContext>,
+) {
+    for input in apps.inputs_for::<PhoneCameraApp>(&mut app_inputs) {
+        let photo_count = gallery.photos.len();
+        match (view.gallery, input) {
+            (None, PhoneInput::Select) => {
+                let Ok((camera, camera_transform)) = viewfinder_query.get_single() else {
+                    continue;
+                };
+
+                let player = player_query.get_single().ok();
+                let subjects = subject_query
+                    .iter()
+                    .filter(|(entity, _, transform)| {
+                        let position = transform.translation();
+                        position.distance(camera_transform.translation()) < MAX_PHOTO_DISTANCE
+                            && camera
+                                .world_to_ndc(camera_transform, position)
+                                .is_some_and(|ndc| {
+                                    ndc.x.abs() <= 1.0
+                                        && ndc.y.abs() <= 1.0
+                                        && (0.0..=1.0).contains(&ndc.z)
+                                })
+                            && !is_occluded(
+                                &rapier_context,
+                                &parent_query,
+                                camera_transform.translation(),
+                                *entity,
+                                position,
+                                player,
+                            )
+                    })
+                    .map(|(_, subject, _)| subject.0.clone())
+                    .collect();
+
+                let photo = PhotoTaken {
+                    id: gallery.next_id,
+                    date: phone.date,
+                    subjects,
+                };
+                gallery.next_id += 1;
+                requests.pending.push(photo.id);
+                gallery.pending.push(photo.clone());
+                photo_events.send(photo);
+            }
+            (None, PhoneInput::Down) if photo_count > 0 => view.gallery = Some(photo_count - 1),
+            (Some(_), PhoneInput::Up) => view.gallery = None,
+            (Some(index), PhoneInput::Left) => view.gallery = Some(index.saturating_sub(1)),
+            (Some(index), PhoneInput::Right) => {
+                view.gallery = Some((index + 1).min(photo_count.saturating_sub(1)))
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Whether something other than `subject` or its own colliders is between the camera and it
+fn is_occluded(
+    rapier_context: &RapierContext,
+    parent_query: &Query<&Parent>,
+    origin: Vec3,
+    subject: Entity,
+    position: Vec3,
+    player: Option<Entity>,
+) -> bool {
+    let offset = position - origin;
+    let filter = QueryFilter {
+        exclude_collider: player,
+        exclude_rigid_body: player,
+        ..default()
+    };
+    let Some((hit, _)) = rapier_context.cast_ray(
+        origin,
+        offset.normalize_or_zero(),
+        offset.length(),
+        false,
+        filter,
+    ) else {
+        return false;
+    };
+    hit != subject
+        && !parent_query
+            .iter_ancestors(hit)
+            .any(|ancestor| ancestor == subject)
+}
+
+fn clear_photo_requests(mut requests: ResMut<PhotoRequests>) {
+    if !requests.pending.is_empty() {
+        requests.pending.clear();
+    }
+}
+
+/// Copies the viewfinder texture into a buffer and hands the pixels back to the main world
+fn copy_photos(
+    requests: Res<PhotoRequests>,
+    images: Res<RenderAssets<Image>>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    sender: Res<PhotoSender>,
+) {
+    if requests.pending.is_empty() {
+        return;
+    }
+    let Some(gpu_image) = images.get(&requests.viewfinder) else {
+        return;
+    };
+
+    let width = gpu_image.size.x as u32;
+    let height = gpu_image.size.y as u32;
+    let row_bytes = width as usize * 4;
+    let padded_row_bytes = RenderDevice::align_copy_bytes_per_row(row_bytes);
+
+    let buffer = render_device.create_buffer(&BufferDescriptor {
+        label: Some("photo_buffer"),
+        size: (padded_row_bytes * height as usize) as u64,
+        usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = render_device.create_command_encoder(&CommandEncoderDescriptor {
+        label: Some("photo_copy"),
+    });
+    encoder.copy_texture_to_buffer(
+        gpu_image.texture.as_image_copy(),
+        ImageCopyBuffer {
+            buffer: &buffer,
+            layout: ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_row_bytes as u32),
+                rows_per_image: None,
+            },
+        },
+        VIEWFINDER_SIZE,
+    );
+    render_queue.submit([encoder.finish()]);
+
+    let Ok(sender) = sender.0.lock().map(|sender| sender.clone()) else {
+        return;
+    };
+    let ids = requests.pending.clone();
+    let readback = buffer.clone();
+    render_device.map_buffer(&buffer.slice(..), MapMode::Read, move |result| {
+        if let Err(error) = result {
+            error!("Couldn't read back photo: {}", error);
+            return;
+        }
+
+        // Strip the row padding wgpu needs for copies
+        let data = readback
+            .slice(..)
+            .get_mapped_range()
+            .chunks(padded_row_bytes)
+            .flat_map(|row| &row[..row_bytes])
+            .copied()
+            .collect::<Vec<u8>>();
+        readback.unmap();
+
+        for id in ids {
+            let _ = sender.send(CapturedPhoto {
+                id,
+                data: data.clone(),
+            });
+        }
+    });
+    // Photos are rare, so wait for the copy here rather than hoping a later submit maps it
+    render_device.poll(Maintain::Wait);
+}
+
+/// Stores read back photos in the gallery and writes them to disk in the background
+fn receive_photos(
+    receiver: Res<PhotoReceiver>,
+    mut gallery: ResMut<Gallery>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let Ok(receiver) = receiver.0.lock() else {
+        return;
+    };
+
+    for captured in receiver.try_iter() {
+        let Some(index) = gallery
+            .pending
+            .iter()
+            .position(|photo| photo.id == captured.id)
+        else {
+            continue;
+        };
+        let taken = gallery.pending.remove(index);
+
+        let image = Image::new(
+            VIEWFINDER_SIZE,
+            TextureDimension::D2,
+            captured.data,
+            TextureFormat::Rgba8UnormSrgb,
+        );
+        let path = PathBuf::from(SCREENSHOT_DIRECTORY).join(format!(
+            "photo_day{}_{:0>2}{:0>2}_{}.png",
+            taken.date.day_number, taken.date.hour, taken.date.minute, taken.id
+        ));
+
+        match image.clone().try_into_dynamic() {
+            Ok(dynamic_image) => {
+                let save_path = path.clone();
+                AsyncComputeTaskPool::get()
+                    .spawn(async move {
+                        let result = std::fs::create_dir_all(SCREENSHOT_DIRECTORY)
+                            .map_err(|error| error.to_string())
+                            .and_then(|_| {
+                                dynamic_image
+                                    .to_rgb8()
+                                    .save(&save_path)
+                                    .map_err(|error| error.to_string())
+                            });
+                        match result {
+                            Ok(()) => info!("Saved photo to {}", save_path.display()),
+                            Err(error) => error!("Couldn't save photo: {}", error),
+                        }
+                    })
+                    .detach();
+            }
+            Err(error) => error!("Couldn't convert photo: {}", error),
+        }
+
+        gallery.photos.push(Photo {
+            id: taken.id,
+            image: images.add(image),
+            path,
+            date: taken.date,
+            subjects: taken.subjects,
+        });
+    }
+}
+
+fn check_photo_objectives(
+    mut commands: Commands,
+    mut photo_events: EventReader<PhotoTaken>,
+    mut objectives: ResMut<PhotoObjectives>,
+    mut completed_events: EventWriter<PhotoObjectiveCompleted>,
+) {
+    for photo in photo_events.iter() {
+        for objective in objectives.0.iter_mut() {
+            if !objective.completed && objective.is_met_by(photo) {
+                objective.completed = true;
+                commands.add(PostNotification(
+                    Notification::new("Photo objective complete", &objective.description)
+                        .from_app::<PhoneCameraApp>(),
+                ));
+                completed_events.send(PhotoObjectiveCompleted {
+                    description: objective.description.clone(),
+                });
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn update_camera_app_ui(
+    view: Res<CameraView>,
+    gallery: Res<Gallery>,
+    requests: Res<PhotoRequests>,
+    objectives: Res<PhotoObjectives>,
+    mut screen_query: Query<&mut Handle<Image>, With<CameraAppScreen>>,
+    mut text_query: Query<&mut Text, With<CameraAppText>>,
+) {
+    if !(view.is_changed() || gallery.is_changed() || objectives.is_changed()) {
+        return;
+    }
+
+    let shown = view.gallery.and_then(|index| gallery.photos().get(index));
+    if let Ok(mut texture) = screen_query.get_single_mut() {
+        *texture = shown
+            .map(|photo| photo.image.clone())
+            .unwrap_or_else(|| requests.viewfinder.clone());
+    }
+
+    if let Ok(mut text) = text_query.get_single_mut() {
+        let mut lines = Vec::new();
+        match (view.gallery, shown) {
+            (Some(index), Some(photo)) => {
+                lines.push(format!("Photo {}/{}", index + 1, gallery.photos().len()));
+                lines.push(photo.date.to_string());
+                lines.extend(photo.subjects.iter().cloned());
+                lines.push("[<][>] Browse".to_string());
+                lines.push("[Up] Camera".to_string());
+            }
+            _ => {
+                lines.push("[Select] Snap".to_string());
+                if !gallery.photos().is_empty() {
+                    lines.push(format!("[Down] Gallery ({})", gallery.photos().len()));
+                }
+                for objective in &objectives.0 {
+                    let mark = if objective.completed { "x" } else { " " };
+                    lines.push(format!("[{}] {}", mark, objective.description));
+                }
+            }
+        }
+        text.sections[0].value = lines.join("\n");
+    }
+}
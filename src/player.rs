@@ -4,6 +4,7 @@ use leafwing_input_manager::prelude::*;
 
 use crate::{
     camera::PrimaryCamera,
+    health::{Health, PLAYER_HEALTH},
     input::{InputListenerBundle, PlayerAction},
     movement::{Character, Direction, Momentum, Movespeed},
     GameState,
@@ -43,6 +44,10 @@ impl PlayerStats {
         self.entity
     }
 
+    pub fn interacted(&self) -> Option<Entity> {
+        self.interacting_object
+    }
+
     pub fn clear_interacted(&mut self) {
         self.interacting_object = None;
     }
@@ -78,6 +83,7 @@ fn spawn_player(mut commands: Commands) {
         .insert(Player)
         .insert(Name::new("Player"))
         .insert(Character)
+        .insert(Health::new(PLAYER_HEALTH))
         .id();
 
     commands.insert_resource(PlayerStats::new(player_entity));
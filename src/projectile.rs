@@ -0,0 +1,140 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{Collider, QueryFilter, RapierContext};
+
+use crate::{
+    health::DamageType,
+    shooting::{hit_entity, ShootableQuery, ShotHit},
+    weapon::ProjectileShot,
+    GameState,
+};
+
+pub struct ProjectilePlugin;
+
+impl Plugin for ProjectilePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (move_projectiles, draw_tracers)
+                .chain()
+                .run_if(in_state(GameState::RunAndGun)),
+        );
+    }
+}
+
+const TRACER_LENGTH: f32 = 0.6;
+
+/// Swept through the physics world each frame so fast shots can't tunnel through thin colliders
+#[derive(Component)]
+pub struct Projectile {
+    pub velocity: Vec3,
+    pub gravity: f32,
+    pub radius: f32,
+    pub base_damage: u16,
+    pub force: f32,
+    pub penetration: f32,
+    pub travelled: f32,
+    pub range: f32,
+    pub lifetime: Timer,
+    /// Never hit by its own projectiles
+    pub shooter: Entity,
+}
+
+#[derive(Bundle)]
+pub struct ProjectileBundle {
+    projectile: Projectile,
+    transform: TransformBundle,
+    name: Name,
+}
+
+impl ProjectileBundle {
+    pub fn new(shot: &ProjectileShot, shooter: Entity) -> Self {
+        ProjectileBundle {
+            projectile: Projectile {
+                velocity: shot.dir * shot.speed,
+                gravity: shot.gravity,
+                radius: shot.radius,
+                base_damage: shot.base_damage,
+                force: shot.force,
+                penetration: shot.penetration,
+                travelled: 0.0,
+                range: shot.range,
+                lifetime: Timer::from_seconds(shot.lifetime, TimerMode::Once),
+                shooter,
+            },
+            transform: TransformBundle::from_transform(Transform::from_translation(shot.origin)),
+            name: Name::new("Projectile"),
+        }
+    }
+}
+
+fn move_projectiles(
+    mut commands: Commands,
+    time: Res<Time>,
+    rapier_context: Res<RapierContext>,
+    shootable_query: ShootableQuery,
+    mut hit_events: EventWriter<ShotHit>,
+    mut projectile_query: Query<(Entity, &mut Projectile, &mut Transform)>,
+) {
+    let delta = time.delta_seconds();
+
+    for (entity, mut projectile, mut transform) in &mut projectile_query {
+        if projectile.lifetime.tick(time.delta()).finished()
+            || projectile.travelled >= projectile.range
+        {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        let velocity = projectile.velocity;
+        let filter = QueryFilter {
+            exclude_collider: Some(projectile.shooter),
+            exclude_rigid_body: Some(projectile.shooter),
+            ..default()
+        };
+
+        if let Some((hit, toi)) = rapier_context.cast_shape(
+            transform.translation,
+            Quat::IDENTITY,
+            velocity,
+            &Collider::ball(projectile.radius),
+            delta,
+            filter,
+        ) {
+            hit_entity(
+                &mut commands,
+                &shootable_query,
+                &mut hit_events,
+                ShotHit {
+                    entity: hit,
+                    shooter: projectile.shooter,
+                    point: toi.witness1,
+                    normal: toi.normal1,
+                    dir: velocity.normalize_or_zero(),
+                    base_damage: projectile.base_damage,
+                    damage_type: DamageType::Projectile,
+                    penetration: projectile.penetration,
+                    distance: projectile.travelled + velocity.length() * toi.toi,
+                    range: projectile.range,
+                },
+                projectile.force,
+            );
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        transform.translation += velocity * delta;
+        projectile.travelled += velocity.length() * delta;
+        projectile.velocity.y -= projectile.gravity * delta;
+    }
+}
+
+fn draw_tracers(projectile_query: Query<(&Projectile, &Transform)>, mut gizmos: Gizmos) {
+    for (projectile, transform) in &projectile_query {
+        let tail = projectile.velocity.normalize_or_zero() * TRACER_LENGTH;
+        gizmos.line(
+            transform.translation - tail,
+            transform.translation,
+            Color::ORANGE,
+        );
+    }
+}
@@ -1,7 +1,20 @@
 use bevy::prelude::*;
 use bevy::utils::HashMap;
 
-#[derive(Resource)]
+use crate::notifications::{Notification, PostNotification};
+
+pub struct QuestPlugin;
+
+impl Plugin for QuestPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<QuestStatusEvent>()
+            .add_event::<QuestProgressEvent>()
+            .init_resource::<QuestLog>()
+            .add_systems(Update, (notify_quest_updates, update_quest_log));
+    }
+}
+
+#[derive(Resource, Default)]
 pub struct QuestLog {
     quests: HashMap<QuestId, Quest>,
 }
@@ -10,6 +23,19 @@ impl QuestLog {
     pub fn quests(&self) -> impl Iterator<Item = &Quest> {
         self.quests.values()
     }
+
+    pub fn get(&self, id: QuestId) -> Option<&Quest> {
+        self.quests.get(&id)
+    }
+
+    /// Adds a quest the player has just learned about, leaving known quests untouched
+    pub fn discover(&mut self, id: QuestId, description: impl Into<String>) {
+        self.quests.entry(id).or_insert_with(|| Quest {
+            description: description.into(),
+            status: QuestStatus::Discovered,
+            progress: 0.0,
+        });
+    }
 }
 
 #[derive(Debug)]
@@ -20,21 +46,62 @@ pub struct Quest {
 }
 
 pub struct ObjectiveId(pub u16);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct QuestId(pub u16);
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum QuestStatus {
     Undiscovered,
     Discovered,
     Completed,
 }
 
+#[derive(Event)]
 pub struct QuestStatusEvent {
     pub id: QuestId,
     pub status: QuestStatus,
 }
 
+#[derive(Event)]
 pub struct QuestProgressEvent {
     pub id: QuestId,
     pub progress: f32,
 }
+
+fn update_quest_log(
+    mut quest_log: ResMut<QuestLog>,
+    mut status_events: EventReader<QuestStatusEvent>,
+    mut progress_events: EventReader<QuestProgressEvent>,
+) {
+    for event in status_events.iter() {
+        if let Some(quest) = quest_log.quests.get_mut(&event.id) {
+            quest.status = event.status;
+        }
+    }
+    for event in progress_events.iter() {
+        if let Some(quest) = quest_log.quests.get_mut(&event.id) {
+            quest.progress = event.progress;
+        }
+    }
+}
+
+fn notify_quest_updates(
+    mut commands: Commands,
+    quest_log: Res<QuestLog>,
+    mut status_events: EventReader<QuestStatusEvent>,
+) {
+    for event in status_events.iter() {
+        let Some(quest) = quest_log.get(event.id) else {
+            continue;
+        };
+        let title = match event.status {
+            QuestStatus::Discovered => "New quest",
+            QuestStatus::Completed => "Quest complete",
+            QuestStatus::Undiscovered => continue,
+        };
+        commands.add(PostNotification(
+            Notification::new(title, &quest.description).with_icon(Color::GOLD),
+        ));
+    }
+}
@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+
+use crate::{
+    camera::{AimMode, FirstPersonGun, PrimaryCamera},
+    inventory::Belt,
+    weapon::ShotEvent,
+    PlayerSet,
+};
+
+pub struct RecoilPlugin;
+
+impl Plugin for RecoilPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RecoilState>().add_systems(
+            Update,
+            (kick_camera, recover_from_recoil)
+                .chain()
+                .in_set(PlayerSet::Combat),
+        );
+    }
+}
+
+/// Where the current spray is in its pattern and how much kick is still to settle
+#[derive(Resource, Default)]
+pub struct RecoilState {
+    shot: usize,
+    since_last_shot: f32,
+    /// Kick not yet recovered, in degrees up and right
+    offset: Vec2,
+}
+
+fn kick_camera(
+    mut shot_events: EventReader<ShotEvent>,
+    mut recoil: ResMut<RecoilState>,
+    belt: Res<Belt>,
+    time: Res<Time>,
+    gun_query: Query<&FirstPersonGun>,
+    mut camera_query: Query<&mut PrimaryCamera>,
+) {
+    let gun = belt.gun();
+    recoil.since_last_shot += time.delta_seconds();
+    if recoil.since_last_shot > gun.recoil().reset_time {
+        recoil.shot = 0;
+    }
+
+    let Ok(mut camera) = camera_query.get_single_mut() else {
+        return;
+    };
+    let aiming = gun_query
+        .iter()
+        .any(|first_person| first_person.0 == AimMode::Sights);
+
+    for _ in shot_events.iter() {
+        let mut kick = gun.get_kick(recoil.shot);
+        if aiming {
+            kick *= gun.recoil().sights;
+        }
+
+        camera.adjust_x_angle(kick.x);
+        camera.adjust_y_angle(-kick.y);
+        recoil.offset += kick;
+        recoil.shot += 1;
+        recoil.since_last_shot = 0.0;
+    }
+}
+
+/// Once a spray ends, drifts the camera back by the kick it took, leaving the player's own aim alone
+fn recover_from_recoil(
+    mut recoil: ResMut<RecoilState>,
+    belt: Res<Belt>,
+    time: Res<Time>,
+    mut camera_query: Query<&mut PrimaryCamera>,
+) {
+    if recoil.shot > 0 || recoil.offset == Vec2::ZERO {
+        return;
+    }
+    let Ok(mut camera) = camera_query.get_single_mut() else {
+        return;
+    };
+
+    let step = belt.gun().recoil().recovery * time.delta_seconds();
+    let recovered = recoil.offset.clamp_length_max(step);
+    camera.adjust_x_angle(-recovered.x);
+    camera.adjust_y_angle(recovered.y);
+    recoil.offset -= recovered;
+}
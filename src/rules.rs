@@ -0,0 +1,179 @@
+use crate::{
+    clock::{Phone, START_DATE},
+    money::{Debts, Money, TransactionCategory, Wallet},
+    GameState,
+};
+use bevy::prelude::*;
+
+pub struct RulesPlugin;
+
+impl Plugin for RulesPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Difficulty::default())
+            .insert_resource(EndConditions::for_difficulty(Difficulty::default()))
+            .add_systems(
+                Update,
+                (
+                    update_end_conditions.run_if(resource_changed::<Difficulty>()),
+                    check_end_conditions.run_if(in_state(GameState::RunAndGun)),
+                )
+                    .chain(),
+            )
+            .add_systems(OnEnter(GameState::Bankrupt), spawn_summary_screen)
+            .add_systems(OnEnter(GameState::DebtFree), spawn_summary_screen);
+    }
+}
+
+#[derive(Resource, Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+/// Thresholds that end a run, checked every frame while playing
+#[derive(Resource, Clone, Debug)]
+pub struct EndConditions {
+    /// Going below this in the wallet is bankruptcy
+    pub funds_floor: Money,
+    /// Total debt at or above this is bankruptcy
+    pub debt_ceiling: Money,
+    /// Missed payments across every debt before the run is lost
+    pub max_missed_payments: u32,
+    /// Total debt at or below this wins the run
+    pub debt_free_threshold: Money,
+}
+
+impl EndConditions {
+    pub fn for_difficulty(difficulty: Difficulty) -> Self {
+        match difficulty {
+            Difficulty::Easy => EndConditions {
+                funds_floor: Money::from_dollars(-1_000),
+                debt_ceiling: Money::from_dollars(5_000_000),
+                max_missed_payments: 12,
+                debt_free_threshold: Money::from_dollars(100),
+            },
+            Difficulty::Normal => EndConditions {
+                funds_floor: Money::from_dollars(-250),
+                debt_ceiling: Money::from_dollars(2_000_000),
+                max_missed_payments: 6,
+                debt_free_threshold: Money::ZERO,
+            },
+            Difficulty::Hard => EndConditions {
+                funds_floor: Money::ZERO,
+                debt_ceiling: Money::from_dollars(1_250_000),
+                max_missed_payments: 3,
+                debt_free_threshold: Money::ZERO,
+            },
+        }
+    }
+
+    pub fn outcome(&self, wallet: &Wallet, debts: &Debts) -> Option<GameState> {
+        if debts.total() <= self.debt_free_threshold {
+            Some(GameState::DebtFree)
+        } else if wallet.funds() < self.funds_floor
+            || debts.total() >= self.debt_ceiling
+            || debts.missed_payments() >= self.max_missed_payments
+        {
+            Some(GameState::Bankrupt)
+        } else {
+            None
+        }
+    }
+}
+
+/// Snapshot of the run taken the moment it ended
+#[derive(Resource, Clone, Debug)]
+pub struct RunSummary {
+    pub days_survived: u32,
+    pub total_earned: Money,
+    pub spent_on_ammo: Money,
+    pub debt_remaining: Money,
+    pub missed_payments: u32,
+}
+
+impl RunSummary {
+    pub fn new(phone: &Phone, wallet: &Wallet, debts: &Debts) -> Self {
+        RunSummary {
+            days_survived: phone.date.day_number - START_DATE.day_number,
+            total_earned: wallet.ledger().total_earned(),
+            spent_on_ammo: -wallet.ledger().total_for(TransactionCategory::Ammo),
+            debt_remaining: debts.total(),
+            missed_payments: debts.missed_payments(),
+        }
+    }
+}
+
+fn update_end_conditions(difficulty: Res<Difficulty>, mut end_conditions: ResMut<EndConditions>) {
+    *end_conditions = EndConditions::for_difficulty(*difficulty);
+}
+
+fn check_end_conditions(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameState>>,
+    end_conditions: Res<EndConditions>,
+    phone: Res<Phone>,
+    wallet: Res<Wallet>,
+    debts: Res<Debts>,
+) {
+    if let Some(outcome) = end_conditions.outcome(&wallet, &debts) {
+        commands.insert_resource(RunSummary::new(&phone, &wallet, &debts));
+        next_state.set(outcome);
+    }
+}
+
+#[derive(Component)]
+pub struct SummaryScreen;
+
+fn spawn_summary_screen(
+    mut commands: Commands,
+    state: Res<State<GameState>>,
+    summary: Res<RunSummary>,
+) {
+    let (title, title_color) = match state.get() {
+        GameState::DebtFree => ("DEBT FREE", Color::GREEN),
+        _ => ("BANKRUPT", Color::RED),
+    };
+
+    let stat_style = TextStyle {
+        font_size: 36.0,
+        color: Color::WHITE,
+        ..default()
+    };
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.85).into(),
+            ..default()
+        })
+        .insert(SummaryScreen)
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                title,
+                TextStyle {
+                    font_size: 96.0,
+                    color: title_color,
+                    ..default()
+                },
+            ));
+            for line in [
+                format!("Days survived: {}", summary.days_survived),
+                format!("Total earned: {}", summary.total_earned),
+                format!("Spent on ammo: {}", summary.spent_on_ammo),
+                format!("Debt remaining: {}", summary.debt_remaining),
+                format!("Missed payments: {}", summary.missed_payments),
+            ] {
+                parent.spawn(TextBundle::from_section(line, stat_style.clone()));
+            }
+        });
+}
@@ -0,0 +1,309 @@
+use crate::clock::{Date, Day};
+use bevy::prelude::*;
+use std::sync::Arc;
+
+pub struct SchedulerPlugin;
+
+impl Plugin for SchedulerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ScheduleFired>()
+            .register_type::<Scheduler>()
+            .init_resource::<Scheduler>();
+    }
+}
+
+pub type ScheduleCallback = Arc<dyn Fn(&mut World, Date) + Send + Sync>;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+pub struct ScheduleId(u32);
+
+#[derive(Clone, Debug, Reflect)]
+pub enum Recurrence {
+    Once,
+    EveryMinutes(u32),
+    Daily { hour: u8, minute: u8 },
+    Weekly { day: Day, hour: u8, minute: u8 },
+}
+
+impl Recurrence {
+    fn next_after(&self, date: Date) -> Option<Date> {
+        match *self {
+            Recurrence::Once => None,
+            Recurrence::EveryMinutes(interval) => Some(date.add_minutes(interval.max(1))),
+            Recurrence::Daily { hour, minute } => Some(date.next_daily(hour, minute)),
+            Recurrence::Weekly { day, hour, minute } => Some(date.next_weekly(day, hour, minute)),
+        }
+    }
+}
+
+#[derive(Reflect)]
+pub struct ScheduledEntry {
+    pub id: ScheduleId,
+    pub label: String,
+    pub recurrence: Recurrence,
+    pub next: Date,
+    pub times_fired: u32,
+    #[reflect(ignore)]
+    callback: Option<ScheduleCallback>,
+}
+
+/// Sent for every scheduled firing, in date order, even if several fired on the same tick
+#[derive(Event, Clone, Debug)]
+pub struct ScheduleFired {
+    pub id: ScheduleId,
+    pub label: String,
+    /// When this firing was due, which can be earlier than the current date after a skip
+    pub date: Date,
+}
+
+/// Things that should happen at in-game dates, driven by `clock::advance_time`
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct Scheduler {
+    now: Date,
+    next_id: u32,
+    entries: Vec<ScheduledEntry>,
+}
+
+impl Scheduler {
+    pub fn new(now: Date) -> Self {
+        Scheduler {
+            now,
+            next_id: 0,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn now(&self) -> Date {
+        self.now
+    }
+
+    /// Moves the scheduler to `now` without firing anything, entries keep their due dates
+    pub fn set_now(&mut self, now: Date) {
+        self.now = now;
+    }
+
+    pub fn entries(&self) -> &[ScheduledEntry] {
+        &self.entries
+    }
+
+    pub fn next_due(&self) -> Option<&ScheduledEntry> {
+        self.entries.iter().min_by_key(|entry| entry.next)
+    }
+
+    pub fn at(&mut self, date: Date, label: impl Into<String>) -> ScheduleId {
+        self.add(date, Recurrence::Once, label.into())
+    }
+
+    pub fn after(&mut self, minutes: u32, label: impl Into<String>) -> ScheduleId {
+        self.add(
+            self.now.add_minutes(minutes),
+            Recurrence::Once,
+            label.into(),
+        )
+    }
+
+    pub fn every(&mut self, minutes: u32, label: impl Into<String>) -> ScheduleId {
+        let minutes = minutes.max(1);
+        self.add(
+            self.now.add_minutes(minutes),
+            Recurrence::EveryMinutes(minutes),
+            label.into(),
+        )
+    }
+
+    pub fn daily(&mut self, hour: u8, minute: u8, label: impl Into<String>) -> ScheduleId {
+        self.add(
+            self.now.next_daily(hour, minute),
+            Recurrence::Daily { hour, minute },
+            label.into(),
+        )
+    }
+
+    pub fn weekly(
+        &mut self,
+        day: Day,
+        hour: u8,
+        minute: u8,
+        label: impl Into<String>,
+    ) -> ScheduleId {
+        self.add(
+            self.now.next_weekly(day, hour, minute),
+            Recurrence::Weekly { day, hour, minute },
+            label.into(),
+        )
+    }
+
+    /// Runs `callback` with world access every time `id` fires
+    pub fn with_callback(
+        &mut self,
+        id: ScheduleId,
+        callback: impl Fn(&mut World, Date) + Send + Sync + 'static,
+    ) -> ScheduleId {
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.id == id) {
+            entry.callback = Some(Arc::new(callback));
+        }
+        id
+    }
+
+    pub fn cancel(&mut self, id: ScheduleId) -> bool {
+        let count = self.entries.len();
+        self.entries.retain(|entry| entry.id != id);
+        self.entries.len() != count
+    }
+
+    fn add(&mut self, next: Date, recurrence: Recurrence, label: String) -> ScheduleId {
+        let id = ScheduleId(self.next_id);
+        self.next_id += 1;
+        self.entries.push(ScheduledEntry {
+            id,
+            label,
+            recurrence,
+            next,
+            times_fired: 0,
+            callback: None,
+        });
+        id
+    }
+
+    /// Fires everything due up to and including `date`, queueing callbacks as commands
+    pub fn fire_until(
+        &mut self,
+        date: Date,
+        commands: &mut Commands,
+        fired_events: &mut EventWriter<ScheduleFired>,
+    ) {
+        for (date, id, label, callback) in self.take_due(date) {
+            if let Some(callback) = callback {
+                commands.add(move |world: &mut World| callback(world, date));
+            }
+            fired_events.send(ScheduleFired { id, label, date });
+        }
+    }
+
+    /// Advances every entry past `date`, returning each firing in date order
+    fn take_due(&mut self, date: Date) -> Vec<Firing> {
+        let mut fired: Vec<Firing> = Vec::new();
+
+        for entry in &mut self.entries {
+            while entry.next <= date {
+                fired.push((
+                    entry.next,
+                    entry.id,
+                    entry.label.clone(),
+                    entry.callback.clone(),
+                ));
+                entry.times_fired += 1;
+                match entry.recurrence.next_after(entry.next) {
+                    Some(next) => entry.next = next,
+                    None => break,
+                }
+            }
+        }
+
+        self.entries.retain(|entry| {
+            !matches!(entry.recurrence, Recurrence::Once) || entry.times_fired == 0
+        });
+        self.now = date;
+
+        fired.sort_by_key(|(date, id, _, _)| (*date, id.0));
+        fired
+    }
+}
+
+type Firing = (Date, ScheduleId, String, Option<ScheduleCallback>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{MINUTES_PER_DAY, MINUTES_PER_HOUR};
+
+    fn fired_dates(scheduler: &mut Scheduler, until: Date) -> Vec<(ScheduleId, Date)> {
+        scheduler
+            .take_due(until)
+            .into_iter()
+            .map(|(date, id, _, _)| (id, date))
+            .collect()
+    }
+
+    #[test]
+    fn repeats_fire_for_every_skipped_interval() {
+        let start = Date::new(Day::Monday, 18, 30);
+        let mut scheduler = Scheduler::new(start);
+        let id = scheduler.every(30, "Tick");
+
+        let fired = fired_dates(&mut scheduler, start.add_minutes(90));
+        assert_eq!(
+            fired,
+            vec![
+                (id, Date::new(Day::Monday, 19, 0)),
+                (id, Date::new(Day::Monday, 19, 30)),
+                (id, Date::new(Day::Monday, 20, 0)),
+            ]
+        );
+        assert_eq!(scheduler.entries()[0].times_fired, 3);
+        assert_eq!(scheduler.entries()[0].next, Date::new(Day::Monday, 20, 30));
+        assert_eq!(scheduler.now(), start.add_minutes(90));
+    }
+
+    #[test]
+    fn daily_rolls_over_days() {
+        let mut scheduler = Scheduler::new(Date::new(Day::Monday, 18, 30));
+        let id = scheduler.daily(8, 0, "Alarm");
+
+        let fired = fired_dates(&mut scheduler, Date::new(Day::Thursday, 8, 0));
+        assert_eq!(
+            fired,
+            vec![
+                (id, Date::new(Day::Tuesday, 8, 0)),
+                (id, Date::new(Day::Wednesday, 8, 0)),
+                (id, Date::new(Day::Thursday, 8, 0)),
+            ]
+        );
+        assert_eq!(scheduler.entries()[0].next, Date::new(Day::Friday, 8, 0));
+    }
+
+    #[test]
+    fn daily_at_the_current_time_waits_a_day() {
+        let mut scheduler = Scheduler::new(Date::new(Day::Monday, 8, 0));
+        scheduler.daily(8, 0, "Alarm");
+        assert_eq!(scheduler.entries()[0].next, Date::new(Day::Tuesday, 8, 0));
+    }
+
+    #[test]
+    fn weekly_rolls_over_weeks() {
+        let start = Date::new(Day::Saturday, 23, 0);
+        let mut scheduler = Scheduler::new(start);
+        let id = scheduler.weekly(Day::Sunday, 12, 0, "Rent");
+
+        let first = start.add_minutes(13 * MINUTES_PER_HOUR);
+        assert_eq!(scheduler.entries()[0].next, first);
+        assert_eq!((first.day, first.day_number), (Day::Sunday, 7));
+
+        let fired = fired_dates(&mut scheduler, first.add_minutes(14 * MINUTES_PER_DAY));
+        let second = first.add_minutes(7 * MINUTES_PER_DAY);
+        let third = second.add_minutes(7 * MINUTES_PER_DAY);
+        assert_eq!(fired, vec![(id, first), (id, second), (id, third)]);
+        assert!(fired.iter().all(|(_, date)| date.day == Day::Sunday));
+        assert_eq!(third.week(), 3);
+    }
+
+    #[test]
+    fn once_fires_a_single_time_in_date_order() {
+        let start = Date::new(Day::Monday, 18, 30);
+        let mut scheduler = Scheduler::new(start);
+        let late = scheduler.after(120, "Late");
+        let early = scheduler.after(15, "Early");
+
+        assert!(fired_dates(&mut scheduler, start.add_minutes(10)).is_empty());
+        let fired = fired_dates(&mut scheduler, start.add_minutes(MINUTES_PER_DAY));
+        assert_eq!(
+            fired,
+            vec![
+                (early, start.add_minutes(15)),
+                (late, start.add_minutes(120))
+            ]
+        );
+        assert!(scheduler.entries().is_empty());
+    }
+}
@@ -1,12 +1,20 @@
+use crate::ammo::AmmoStock;
 use crate::audio::{EmptySound, SoundBank};
-use crate::camera::CameraFocus;
-use crate::hud::AmmoDisplay;
+use crate::camera::{CameraFocus, FirstPersonGun};
+use crate::clock::Phone;
+use crate::health::DamageType;
+use crate::hud::{AmmoDisplay, FireModeDisplay};
 use crate::inventory::Belt;
 use crate::money::Wallet;
-use crate::weapon::{FireResult, ShotEvent, TriggerMode};
-use crate::{input::PlayerAction, player::Player, GameState, PlayerSet};
+use crate::notifications::{Notification, PostNotification};
+use crate::projectile::ProjectileBundle;
+use crate::surface::{surface_of, SurfaceQuery};
+use crate::weapon::{
+    FireResult, Gun, GunDefinition, GunDefinitionLoader, RaycastShot, ShotEvent, TriggerInput,
+};
+use crate::{input::PlayerAction, player::Player, AssetCache, GameState, PlayerSet};
 use bevy::prelude::*;
-use bevy_rapier3d::prelude::{ExternalImpulse, RapierContext, RigidBody};
+use bevy_rapier3d::prelude::{ExternalImpulse, QueryFilter, RapierContext, RigidBody};
 use leafwing_input_manager::prelude::*;
 
 pub struct ShootingPlugin;
@@ -14,19 +22,30 @@ pub struct ShootingPlugin;
 impl Plugin for ShootingPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<ShotEvent>()
+            .add_event::<ShotHit>()
+            .add_asset::<GunDefinition>()
+            .init_asset_loader::<GunDefinitionLoader>()
             .insert_resource(Belt::default())
             .configure_set(
                 Update,
                 PlayerSet::Combat.run_if(in_state(GameState::RunAndGun)),
             )
+            .add_systems(OnEnter(GameState::RunAndGun), equip_starting_guns)
+            .add_systems(
+                Update,
+                (reload_gun_definitions, show_gun_model)
+                    .chain()
+                    .run_if(in_state(GameState::RunAndGun)),
+            )
             .add_systems(
                 Update,
                 (
+                    switch_weapons,
                     send_shot_events,
                     read_shot_events,
-                    render_bulletholes,
                     gun_upkeep,
                     track_ammo,
+                    track_fire_mode,
                     rotate_clip,
                     reload_gun,
                 )
@@ -35,13 +54,99 @@ impl Plugin for ShootingPlugin {
     }
 }
 
+const STARTING_GUNS: [&str; 4] = [
+    "data/guns/rifle.gun.ron",
+    "data/guns/pistol.gun.ron",
+    "data/guns/shotgun.gun.ron",
+    "data/guns/nailgun.gun.ron",
+];
+
+fn equip_starting_guns(
+    mut belt: ResMut<Belt>,
+    asset_cache: Res<AssetCache>,
+    definitions: Res<Assets<GunDefinition>>,
+    assets: Res<AssetServer>,
+) {
+    for path in STARTING_GUNS {
+        let Some((handle, definition)) = asset_cache
+            .guns
+            .get(path)
+            .and_then(|handle| Some((handle, definitions.get(handle)?)))
+        else {
+            warn!("No gun definition at {}", path);
+            continue;
+        };
+        belt.add(Gun::from_definition(handle.clone(), definition, &assets));
+    }
+}
+
+/// Lets gun definitions be tuned while the game is running
+fn reload_gun_definitions(
+    mut definition_events: EventReader<AssetEvent<GunDefinition>>,
+    mut belt: ResMut<Belt>,
+    definitions: Res<Assets<GunDefinition>>,
+    assets: Res<AssetServer>,
+) {
+    for event in definition_events.iter() {
+        if let AssetEvent::Modified { handle } = event {
+            let Some(definition) = definitions.get(handle) else {
+                continue;
+            };
+            for gun in belt.guns_mut() {
+                if gun.definition() == handle {
+                    gun.apply_definition(definition, &assets);
+                    info!("Reloaded {}", definition.name);
+                }
+            }
+        }
+    }
+}
+
+fn show_gun_model(belt: Res<Belt>, mut gun_query: Query<&mut Handle<Scene>, With<FirstPersonGun>>) {
+    for mut scene in &mut gun_query {
+        if *scene != *belt.gun().model() {
+            *scene = belt.gun().model().clone();
+        }
+    }
+}
+
 pub fn gun_upkeep(time: Res<Time>, mut belt: ResMut<Belt>) {
-    belt.gun.tick(time.delta());
+    belt.tick(time.delta());
+}
+
+pub fn track_ammo(
+    mut display_query: Query<&mut Text, With<AmmoDisplay>>,
+    belt: Res<Belt>,
+    stock: Res<AmmoStock>,
+) {
+    let gun = belt.gun();
+    let spare = stock.count(gun.caliber(), gun.ammo_type());
+    let next = if gun.ammo_type() != gun.loaded_ammo_type() {
+        format!(" -> {}", gun.ammo_type())
+    } else {
+        String::new()
+    };
+    for mut text in &mut display_query {
+        text.sections[0].value = gun.current_ammo().to_string();
+        text.sections[1].value = format!("/{}", spare);
+        text.sections[2].value = format!(" {}{}", gun.loaded_ammo_type(), next);
+    }
 }
 
-pub fn track_ammo(mut display_query: Query<&mut Text, With<AmmoDisplay>>, belt: Res<Belt>) {
+fn track_fire_mode(mut display_query: Query<&mut Text, With<FireModeDisplay>>, belt: Res<Belt>) {
+    let gun = belt.gun();
+    let mode = match gun.charge() {
+        Some(charge) => format!("Charge {:.0}%", charge * 100.0),
+        None if gun.is_cycling() => format!("{} (cycling)", gun.get_trigger_mode()),
+        None => gun.get_trigger_mode().to_string(),
+    };
+    let hint = if gun.trigger_modes().len() > 1 {
+        " [B]"
+    } else {
+        ""
+    };
     for mut text in &mut display_query {
-        text.sections[0].value = belt.gun.current_ammo().to_string();
+        text.sections[0].value = format!("{}{}", mode, hint);
     }
 }
 
@@ -54,7 +159,7 @@ fn rotate_clip(
     mut clip_query: Query<&mut Transform, With<ClipComponent>>,
 ) {
     for mut transform in &mut clip_query {
-        let rotation_speed = if belt.gun.is_reloading() {
+        let rotation_speed = if belt.gun().is_reloading() {
             50.0_f32.to_radians()
         } else {
             25.0_f32.to_radians()
@@ -66,19 +171,68 @@ fn rotate_clip(
 fn reload_gun(
     mut commands: Commands,
     mut belt: ResMut<Belt>,
+    mut stock: ResMut<AmmoStock>,
     mut wallet: ResMut<Wallet>,
+    phone: Res<Phone>,
     player_query: Query<&ActionState<PlayerAction>>,
 ) {
     if let Ok(action) = player_query.get_single() {
-        if !belt.gun.is_reloading() && action.just_pressed(PlayerAction::Reload) {
-            let pop_up_bundle = belt.gun.reload(wallet);
-            if let Some(pop_up) = pop_up_bundle {
-                commands.add(pop_up);
+        if !belt.gun().is_reloading()
+            && !belt.is_switching()
+            && action.just_pressed(PlayerAction::Reload)
+        {
+            let gun = belt.gun_mut();
+            let reload = gun.reload(&mut stock, &mut wallet, phone.date);
+            if let Some(payment) = reload.payment {
+                commands.add(payment);
+            }
+            if reload.short > 0 {
+                commands.add(PostNotification(Notification::new(
+                    "Can't afford",
+                    format!(
+                        "{} rounds short of a full clip, {} {} costs {} a round",
+                        reload.short,
+                        gun.ammo_type(),
+                        gun.caliber(),
+                        gun.ammo_type().field_price(gun.caliber())
+                    ),
+                )));
             }
         }
     }
 }
 
+const SELECT_WEAPON_ACTIONS: [PlayerAction; 4] = [
+    PlayerAction::SelectWeapon1,
+    PlayerAction::SelectWeapon2,
+    PlayerAction::SelectWeapon3,
+    PlayerAction::SelectWeapon4,
+];
+
+fn switch_weapons(
+    mut belt: ResMut<Belt>,
+    player_query: Query<&ActionState<PlayerAction>, With<Player>>,
+) {
+    let Ok(action) = player_query.get_single() else {
+        return;
+    };
+
+    if action.just_pressed(PlayerAction::NextWeapon) {
+        belt.select_next();
+    }
+    if action.just_pressed(PlayerAction::PreviousWeapon) {
+        belt.select_previous();
+    }
+    if action.just_pressed(PlayerAction::FireSelect) && !belt.is_switching() {
+        belt.gun_mut().cycle_fire_mode();
+    }
+    for (slot, select) in SELECT_WEAPON_ACTIONS.into_iter().enumerate() {
+        if action.just_pressed(select) {
+            belt.select(slot);
+        }
+    }
+}
+
 pub fn send_shot_events(
     mut commands: Commands,
     mut player_query: Query<&ActionState<PlayerAction>, With<Player>>,
@@ -90,27 +244,17 @@ pub fn send_shot_events(
 ) {
     let action = player_query.single_mut();
 
-    let shot_to_fire = match belt.get_trigger_mode() {
-        TriggerMode::Auto => {
-            if action.pressed(PlayerAction::Shoot) {
-                belt.fire(camera_focus)
-            } else {
-                FireResult::NoAction
-            }
-        }
-        TriggerMode::SemiAuto => {
-            if action.just_pressed(PlayerAction::Shoot) {
-                belt.fire(camera_focus)
-            } else {
-                FireResult::NoAction
-            }
-        }
+    let input = TriggerInput {
+        pressed: action.pressed(PlayerAction::Shoot),
+        just_pressed: action.just_pressed(PlayerAction::Shoot),
+        just_released: action.just_released(PlayerAction::Shoot),
     };
+    let shot_to_fire = belt.pull_trigger(input, &camera_focus);
     match shot_to_fire {
         FireResult::Shot(shot) => shot_events.send(shot),
         FireResult::EmptyClip => {
             if empty_query.is_empty() {
-                commands.spawn(sound_bank.empty_fire());
+                commands.spawn(sound_bank.empty_fire(belt.gun()));
             }
         }
         _ => (),
@@ -120,81 +264,188 @@ pub fn send_shot_events(
 #[derive(Component)]
 pub struct Shootable;
 
+/// Sent whenever a bullet or projectile strikes something
+#[derive(Event, Clone, Debug)]
+pub struct ShotHit {
+    pub entity: Entity,
+    pub shooter: Entity,
+    pub point: Vec3,
+    /// Faces out of the surface that was hit
+    pub normal: Vec3,
+    pub dir: Vec3,
+    pub base_damage: u16,
+    pub damage_type: DamageType,
+    /// Taken off the target's armor
+    pub penetration: f32,
+    /// How far the shot travelled before hitting, out of its `range`
+    pub distance: f32,
+    pub range: f32,
+}
+
+pub type ShootableQuery<'w, 's> = Query<
+    'w,
+    's,
+    (&'static Transform, bevy::ecs::query::Has<ExternalImpulse>),
+    (With<RigidBody>, With<Shootable>, Without<Player>),
+>;
+
+/// Knocks `Shootable`s away from the shot and lets everything else know it hit
+pub fn hit_entity(
+    commands: &mut Commands,
+    shootable_query: &ShootableQuery,
+    hit_events: &mut EventWriter<ShotHit>,
+    hit: ShotHit,
+    force: f32,
+) {
+    if let Ok((hit_transform, has_external)) = shootable_query.get(hit.entity) {
+        let center_of_mass = hit_transform.translation;
+        let impulse = ExternalImpulse::at_point(hit.dir * 10.0 * force, hit.point, center_of_mass);
+
+        if has_external {
+            commands
+                .entity(hit.entity)
+                .remove::<ExternalImpulse>()
+                .insert(impulse);
+        } else {
+            commands.entity(hit.entity).insert(impulse);
+        }
+    }
+    hit_events.send(hit);
+}
+
+/// Surfaces a hitscan shot can hit, bounce off or go through before it's spent
+const MAX_SURFACES: usize = 4;
+/// What's left of a shot's damage after it glances off something
+const RICOCHET_DAMAGE: f32 = 0.5;
+/// Keeps rays from hitting the surface they're leaving
+const SURFACE_OFFSET: f32 = 0.01;
+
+/// Follows a hitscan shot through whatever it can penetrate and off whatever it ricochets from,
+/// returning every hit along the way with the force it still carries
+fn trace_hitscan(
+    rapier_context: &RapierContext,
+    surface_query: &SurfaceQuery,
+    shooter: Entity,
+    shot: &RaycastShot,
+) -> Vec<(ShotHit, f32)> {
+    let mut hits = Vec::new();
+    let mut origin = shot.origin;
+    let mut dir = shot.dir;
+    let mut travelled = 0.0;
+    // Fraction of the shot's damage and force that's left
+    let mut strength = 1.0;
+    // Spent going through surfaces, standard rounds start with 1
+    let mut power = 1.0 + shot.penetration;
+    let mut passed_through = vec![shooter];
+
+    for _ in 0..MAX_SURFACES {
+        let not_passed = |entity: Entity| !passed_through.contains(&entity);
+        let Some((entity, intersection)) = rapier_context.cast_ray_and_get_normal(
+            origin,
+            dir,
+            shot.range - travelled,
+            false,
+            QueryFilter::default().predicate(&not_passed),
+        ) else {
+            break;
+        };
+        travelled += intersection.toi;
+        hits.push((
+            ShotHit {
+                entity,
+                shooter,
+                point: intersection.point,
+                normal: intersection.normal,
+                dir,
+                base_damage: (shot.base_damage as f32 * strength).round() as u16,
+                damage_type: DamageType::Bullet,
+                penetration: shot.penetration,
+                distance: travelled,
+                range: shot.range,
+            },
+            shot.force * strength,
+        ));
+
+        let surface = surface_of(entity, surface_query);
+        let normal = intersection.normal;
+        if surface.ricochets(dir, normal) {
+            dir = (dir - 2.0 * dir.dot(normal) * normal).normalize_or_zero();
+            origin = intersection.point + normal * SURFACE_OFFSET;
+            strength *= RICOCHET_DAMAGE;
+            continue;
+        }
+
+        // Look back from as deep as the shot could go to find where it would come out
+        let depth = power / surface.density;
+        if depth <= SURFACE_OFFSET {
+            break;
+        }
+        let probe = intersection.point + dir * depth;
+        let only_this = |other: Entity| other == entity;
+        let Some((_, toi)) = rapier_context.cast_ray(
+            probe,
+            -dir,
+            depth,
+            true,
+            QueryFilter::default().predicate(&only_this),
+        ) else {
+            break;
+        };
+        // The probe started inside, so it's thicker than the shot can get through
+        if toi <= 0.0 {
+            break;
+        }
+
+        let thickness = depth - toi;
+        power -= thickness * surface.density;
+        strength *= 1.0 - surface.damage_loss;
+        if strength <= 0.0 {
+            break;
+        }
+        passed_through.push(entity);
+        origin = probe - dir * (toi - SURFACE_OFFSET);
+        travelled += thickness;
+    }
+    hits
+}
+
+#[allow(clippy::too_many_arguments)]
 fn read_shot_events(
     mut commands: Commands,
     mut shot_events: EventReader<ShotEvent>,
+    mut hit_events: EventWriter<ShotHit>,
     player_query: Query<Entity, With<Player>>,
-    shootable_query: Query<
-        (&Transform, bevy::ecs::query::Has<ExternalImpulse>),
-        (With<RigidBody>, With<Shootable>, Without<Player>),
-    >,
+    shootable_query: ShootableQuery,
+    surface_query: SurfaceQuery,
     sound_bank: Res<SoundBank>,
+    belt: Res<Belt>,
     rapier_context: Res<RapierContext>,
 ) {
     if let Ok(entity) = player_query.get_single() {
         for shot_event in shot_events.iter() {
-            commands.spawn(sound_bank.bullet_shot());
+            commands.spawn(sound_bank.bullet_shot(belt.gun()));
             match shot_event {
                 ShotEvent::Raycast(shots) => {
                     for shot in shots {
-                        let ray_origin = shot.origin;
-                        let ray_dir = shot.dir;
-                        let max_distance = shot.range;
-                        let solid = false;
-                        let filter = bevy_rapier3d::pipeline::QueryFilter {
-                            exclude_collider: Some(entity),
-                            exclude_rigid_body: Some(entity),
-                            ..default()
-                        };
-
-                        if let Some((entity, intersection)) = rapier_context
-                            .cast_ray_and_get_normal(
-                                ray_origin,
-                                ray_dir,
-                                max_distance,
-                                solid,
-                                filter,
-                            )
+                        for (hit, force) in
+                            trace_hitscan(&rapier_context, &surface_query, entity, shot)
                         {
-                            //Bullet Hole
-                            commands.spawn((
-                                TransformBundle::from_transform(
-                                    Transform::default().with_translation(intersection.point),
-                                ),
-                                BulletHole,
-                                Name::new("Hole"),
-                            ));
-                            if let Ok((hit_transform, has_external)) = shootable_query.get(entity) {
-                                let center_of_mass = hit_transform.translation;
-                                let impulse = ExternalImpulse::at_point(
-                                    shot.dir * 10.0,
-                                    intersection.point,
-                                    center_of_mass,
-                                );
-
-                                if has_external {
-                                    commands
-                                        .entity(entity)
-                                        .remove::<ExternalImpulse>()
-                                        .insert(impulse);
-                                } else {
-                                    commands.entity(entity).insert(impulse);
-                                }
-                            }
+                            hit_entity(
+                                &mut commands,
+                                &shootable_query,
+                                &mut hit_events,
+                                hit,
+                                force,
+                            );
                         }
                     }
                 }
-                ShotEvent::Projectile(shots) => {}
+                ShotEvent::Projectile(shots) => {
+                    for shot in shots {
+                        commands.spawn(ProjectileBundle::new(shot, entity));
+                    }
+                }
             }
         }
     }
 }
-
-#[derive(Component)]
-pub struct BulletHole;
-
-pub fn render_bulletholes(hole_query: Query<&Transform, With<BulletHole>>, mut gizmos: Gizmos) {
-    for transform in &hole_query {
-        gizmos.sphere(transform.translation, Quat::default(), 0.1, Color::RED);
-    }
-}
@@ -0,0 +1,302 @@
+use bevy::prelude::*;
+
+use crate::{
+    clock::{self, Date, HourPassed, Phone, TimeScale},
+    messages::MessageReceived,
+    money::{DebtEvent, Money, Wallet},
+    movement,
+    scheduler::{ScheduleFired, Scheduler},
+    GameState, PlayerSet,
+};
+
+pub struct SleepPlugin;
+
+impl Plugin for SleepPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<SleepState>()
+            .add_event::<SleepEvent>()
+            .init_resource::<SleepReport>()
+            .configure_set(
+                Update,
+                PlayerSet::Movement.run_if(in_state(SleepState::Awake)),
+            )
+            .configure_set(
+                Update,
+                PlayerSet::Combat.run_if(in_state(SleepState::Awake)),
+            )
+            .add_systems(
+                Update,
+                fall_asleep
+                    .run_if(in_state(SleepState::Awake))
+                    .run_if(in_state(GameState::RunAndGun)),
+            )
+            .add_systems(
+                OnEnter(SleepState::FallingAsleep),
+                (
+                    movement::disable_physics_simulation,
+                    pause_clock,
+                    spawn_fade_overlay,
+                ),
+            )
+            .add_systems(OnEnter(SleepState::WakingUp), skip_time)
+            .add_systems(
+                OnExit(SleepState::WakingUp),
+                (
+                    movement::re_enable_physics_simulation,
+                    resume_clock,
+                    despawn_fade_overlay,
+                    show_sleep_report,
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    fade.run_if(not(in_state(SleepState::Awake))),
+                    record_debt_events.run_if(not(in_state(SleepState::Awake))),
+                    record_messages.run_if(not(in_state(SleepState::Awake))),
+                    dismiss_sleep_report.run_if(resource_exists::<ReportTimer>()),
+                ),
+            );
+    }
+}
+
+#[derive(States, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SleepState {
+    #[default]
+    Awake,
+    FallingAsleep,
+    WakingUp,
+}
+
+/// Asks the player to sleep until the next time the clock reads `hour`:`minute`
+#[derive(Event, Clone, Copy)]
+pub struct SleepEvent {
+    pub hour: u8,
+    pub minute: u8,
+}
+
+/// Everything that happened between falling asleep and waking up
+#[derive(Resource, Default, Debug)]
+pub struct SleepReport {
+    pub fell_asleep: Date,
+    pub woke_up: Date,
+    funds_before: Money,
+    lines: Vec<String>,
+}
+
+impl SleepReport {
+    pub fn push(&mut self, line: impl Into<String>) {
+        self.lines.push(line.into());
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+}
+
+#[derive(Resource)]
+struct Bedtime {
+    wake_at: Date,
+    fade: Timer,
+}
+
+#[derive(Resource)]
+struct ReportTimer(Timer);
+
+#[derive(Component)]
+struct FadeOverlay;
+
+#[derive(Component)]
+struct SleepReportDisplay;
+
+const FADE_SECONDS: f32 = 1.5;
+const REPORT_SECONDS: f32 = 6.0;
+
+fn fall_asleep(
+    mut commands: Commands,
+    mut sleep_events: EventReader<SleepEvent>,
+    mut next_state: ResMut<NextState<SleepState>>,
+    phone: Res<Phone>,
+    wallet: Res<Wallet>,
+) {
+    if let Some(event) = sleep_events.iter().last() {
+        commands.insert_resource(Bedtime {
+            wake_at: phone.date.next_daily(event.hour, event.minute),
+            fade: Timer::from_seconds(FADE_SECONDS, TimerMode::Once),
+        });
+        commands.insert_resource(SleepReport {
+            fell_asleep: phone.date,
+            woke_up: phone.date,
+            funds_before: wallet.funds(),
+            lines: Vec::new(),
+        });
+        next_state.set(SleepState::FallingAsleep);
+    }
+}
+
+fn pause_clock(mut time_scale: ResMut<TimeScale>) {
+    time_scale.pause();
+}
+
+fn resume_clock(mut time_scale: ResMut<TimeScale>) {
+    time_scale.resume();
+}
+
+fn spawn_fade_overlay(mut commands: Commands) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.0).into(),
+            z_index: ZIndex::Global(100),
+            ..default()
+        })
+        .insert(FadeOverlay);
+}
+
+fn despawn_fade_overlay(mut commands: Commands, overlay_query: Query<Entity, With<FadeOverlay>>) {
+    for entity in &overlay_query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn fade(
+    time: Res<Time>,
+    state: Res<State<SleepState>>,
+    mut next_state: ResMut<NextState<SleepState>>,
+    mut bedtime: ResMut<Bedtime>,
+    mut overlay_query: Query<&mut BackgroundColor, With<FadeOverlay>>,
+) {
+    bedtime.fade.tick(time.delta());
+    let progress = bedtime.fade.percent();
+    let alpha = match state.get() {
+        SleepState::WakingUp => 1.0 - progress,
+        _ => progress,
+    };
+    for mut background in &mut overlay_query {
+        background.0.set_a(alpha);
+    }
+
+    if bedtime.fade.finished() {
+        next_state.set(match state.get() {
+            SleepState::FallingAsleep => SleepState::WakingUp,
+            _ => SleepState::Awake,
+        });
+    }
+}
+
+/// Jumps the clock to the wake up time without simulating anything in between
+fn skip_time(
+    mut commands: Commands,
+    mut bedtime: ResMut<Bedtime>,
+    mut phone: ResMut<Phone>,
+    mut scheduler: ResMut<Scheduler>,
+    mut report: ResMut<SleepReport>,
+    mut hour_events: EventWriter<HourPassed>,
+    mut fired_events: EventWriter<ScheduleFired>,
+) {
+    let fell_asleep = phone.date;
+    phone.date = bedtime.wake_at;
+    clock::pass_time(
+        fell_asleep,
+        phone.date,
+        &mut scheduler,
+        &mut commands,
+        &mut hour_events,
+        &mut fired_events,
+    );
+    report.woke_up = phone.date;
+    bedtime.fade.reset();
+}
+
+fn record_debt_events(mut debt_events: EventReader<DebtEvent>, mut report: ResMut<SleepReport>) {
+    for event in debt_events.iter() {
+        match event {
+            DebtEvent::Billed { debt, amount } => {
+                report.push(format!("{} bill: {}", debt, amount));
+            }
+            DebtEvent::InterestCharged { debt, amount } => {
+                report.push(format!("{} interest: {}", debt, amount));
+            }
+            DebtEvent::MissedPayment { debt, late_fee, .. } => {
+                report.push(format!("Missed {} payment, late fee {}", debt, late_fee));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn record_messages(
+    mut message_events: EventReader<MessageReceived>,
+    mut report: ResMut<SleepReport>,
+) {
+    for message in message_events.iter() {
+        report.push(format!("Text from {}", message.contact));
+    }
+}
+
+fn show_sleep_report(mut commands: Commands, mut report: ResMut<SleepReport>, wallet: Res<Wallet>) {
+    let change = wallet.funds() - report.funds_before;
+    if !change.is_zero() {
+        report.push(format!("Wallet: {:+}", change));
+    }
+    if report.lines().is_empty() {
+        report.push("Nothing happened");
+    }
+
+    let line_style = TextStyle {
+        font_size: 28.0,
+        color: Color::WHITE,
+        ..default()
+    };
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(20.0),
+                left: Val::Px(20.0),
+                padding: UiRect::all(Val::Px(12.0)),
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+            ..default()
+        })
+        .insert(SleepReportDisplay)
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                format!("Slept {} -> {}", report.fell_asleep, report.woke_up),
+                TextStyle {
+                    font_size: 36.0,
+                    ..line_style.clone()
+                },
+            ));
+            for line in report.lines() {
+                parent.spawn(TextBundle::from_section(line.clone(), line_style.clone()));
+            }
+        });
+    commands.insert_resource(ReportTimer(Timer::from_seconds(
+        REPORT_SECONDS,
+        TimerMode::Once,
+    )));
+    commands.remove_resource::<Bedtime>();
+}
+
+fn dismiss_sleep_report(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut report_timer: ResMut<ReportTimer>,
+    report_query: Query<Entity, With<SleepReportDisplay>>,
+) {
+    if report_timer.0.tick(time.delta()).just_finished() {
+        for entity in &report_query {
+            commands.entity(entity).despawn_recursive();
+        }
+        commands.remove_resource::<ReportTimer>();
+    }
+}
@@ -0,0 +1,117 @@
+use bevy::prelude::*;
+
+pub struct SurfacePlugin;
+
+impl Plugin for SurfacePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<SurfaceKind>()
+            .register_type::<SurfaceMaterial>();
+    }
+}
+
+#[derive(Reflect, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SurfaceKind {
+    #[default]
+    Default,
+    Wood,
+    Metal,
+    Concrete,
+    Glass,
+    Flesh,
+}
+
+/// How shots behave when they hit something, can be tagged on gltf nodes. Colliders without
+/// one use the closest one on their ancestors, or `SurfaceMaterial::default`
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component, Default)]
+pub struct SurfaceMaterial {
+    pub kind: SurfaceKind,
+    /// Penetration a shot spends per metre of this material
+    pub density: f32,
+    /// Fraction of a shot's damage lost punching through
+    pub damage_loss: f32,
+    /// Shots coming in shallower than this many degrees off the surface bounce off
+    pub ricochet_angle: f32,
+}
+
+impl Default for SurfaceMaterial {
+    fn default() -> Self {
+        SurfaceMaterial {
+            kind: SurfaceKind::Default,
+            density: 1000.0,
+            damage_loss: 1.0,
+            ricochet_angle: 0.0,
+        }
+    }
+}
+
+impl SurfaceMaterial {
+    pub fn wood() -> Self {
+        SurfaceMaterial {
+            kind: SurfaceKind::Wood,
+            density: 4.0,
+            damage_loss: 0.3,
+            ricochet_angle: 0.0,
+        }
+    }
+
+    pub fn metal() -> Self {
+        SurfaceMaterial {
+            kind: SurfaceKind::Metal,
+            density: 20.0,
+            damage_loss: 0.5,
+            ricochet_angle: 25.0,
+        }
+    }
+
+    pub fn concrete() -> Self {
+        SurfaceMaterial {
+            kind: SurfaceKind::Concrete,
+            density: 40.0,
+            damage_loss: 0.7,
+            ricochet_angle: 15.0,
+        }
+    }
+
+    pub fn glass() -> Self {
+        SurfaceMaterial {
+            kind: SurfaceKind::Glass,
+            density: 1.0,
+            damage_loss: 0.1,
+            ricochet_angle: 0.0,
+        }
+    }
+
+    pub fn flesh() -> Self {
+        SurfaceMaterial {
+            kind: SurfaceKind::Flesh,
+            density: 3.0,
+            damage_loss: 0.4,
+            ricochet_angle: 0.0,
+        }
+    }
+
+    /// Whether a shot travelling along `dir` glances off a surface facing `normal`
+    pub fn ricochets(&self, dir: Vec3, normal: Vec3) -> bool {
+        let angle = (-dir.dot(normal)).clamp(-1.0, 1.0).asin().to_degrees();
+        angle < self.ricochet_angle
+    }
+}
+
+pub type SurfaceQuery<'w, 's> =
+    Query<'w, 's, (Option<&'static SurfaceMaterial>, Option<&'static Parent>)>;
+
+/// The material of `entity`, or of the nearest ancestor that has one
+pub fn surface_of(entity: Entity, surface_query: &SurfaceQuery) -> SurfaceMaterial {
+    let mut current = entity;
+    while let Ok((material, parent)) = surface_query.get(current) {
+        if let Some(material) = material {
+            return *material;
+        }
+        let Some(parent) = parent else {
+            break;
+        };
+        current = parent.get();
+    }
+    SurfaceMaterial::default()
+}
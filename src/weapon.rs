@@ -1,62 +1,275 @@
 use crate::{
+    ammo::{AmmoStock, AmmoType, Caliber},
     camera::CameraFocus,
-    money::{Money, Wallet},
+    clock::Date,
+    money::{TransactionCategory, Wallet, WalletNotification},
+    parts::{GunPart, Incompatibility, PartModifiers, PartSlot},
 };
-use bevy::prelude::*;
-use std::time::Duration;
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+use std::{fmt, time::Duration};
+
+/// A gun's stats, model and sounds, loaded from a `.gun.ron` file
+#[derive(Deserialize, TypeUuid, TypePath, Clone, Default)]
+#[uuid = "0b4c1f2e-7d5a-4e8b-9a61-3f2d8c7e5b14"]
+pub struct GunDefinition {
+    pub name: String,
+    pub model: String,
+    /// Which family of parts fit this gun
+    #[serde(default)]
+    pub platform: String,
+    #[serde(default)]
+    pub shot_sound: Option<String>,
+    #[serde(default)]
+    pub empty_sound: Option<String>,
+    muzzle: Muzzle,
+    receiver: Receiver,
+    clip: Clip,
+    trigger: Trigger,
+}
+
+#[derive(Default)]
+pub struct GunDefinitionLoader;
+
+impl AssetLoader for GunDefinitionLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let definition = ron::de::from_bytes::<GunDefinition>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(definition));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["gun.ron"]
+    }
+}
 
+/// Stands in until a gun is built from a `GunDefinition`, it has no ammo to fire
+#[derive(Default)]
 pub struct Gun {
+    definition: Handle<GunDefinition>,
+    name: String,
+    model: Handle<Scene>,
+    shot_sound: Option<Handle<AudioSource>>,
+    empty_sound: Option<Handle<AudioSource>>,
+    /// Stats before any parts are applied
+    base: GunDefinition,
+    parts: Vec<GunPart>,
     muzzle: Muzzle,
     receiver: Receiver,
     clip: Clip,
     trigger: Trigger,
+    /// Loaded on the next reload
+    ammo_type: AmmoType,
     reload_timer: Timer,
     reloading: bool,
+    /// Rounds going into the clip once the reload finishes
+    reload_pending: Option<(AmmoType, u8)>,
 }
 
-impl Default for Gun {
-    fn default() -> Self {
-        Gun {
-            muzzle: Muzzle {
-                min_spread: 0.0,
-                max_spread: 30.0,
-                current_spread: 0.0,
-                bloom: 1.0,
-                max_range: 30.0,
-            },
-            receiver: Receiver {
-                fire_type: FireType::Hitscan,
-                base_damage: 10,
-                force_transfer: 1.0,
-                kick: 2.0,
-            },
-            clip: Clip::default(),
-            trigger: Trigger::auto(),
-            reload_timer: Timer::default(),
-            reloading: false,
+impl Gun {
+    pub fn from_definition(
+        handle: Handle<GunDefinition>,
+        definition: &GunDefinition,
+        assets: &AssetServer,
+    ) -> Self {
+        let mut gun = Gun {
+            definition: handle,
+            ..default()
+        };
+        gun.apply_definition(definition, assets);
+        gun.clip.current = gun.clip.max;
+        gun
+    }
+
+    /// Takes on the stats in `definition` but keeps the loaded ammo and installed parts, so it
+    /// can be hot reloaded
+    pub fn apply_definition(&mut self, definition: &GunDefinition, assets: &AssetServer) {
+        self.name = definition.name.clone();
+        self.model = assets.load(definition.model.as_str());
+        self.shot_sound = definition
+            .shot_sound
+            .as_ref()
+            .map(|path| assets.load(path.as_str()));
+        self.empty_sound = definition
+            .empty_sound
+            .as_ref()
+            .map(|path| assets.load(path.as_str()));
+        self.base = definition.clone();
+        self.assemble();
+    }
+
+    /// Rebuilds the stats from the definition and installed parts, keeping the loaded ammo.
+    /// Returns how many loaded rounds no longer fit in the clip
+    fn assemble(&mut self) -> u32 {
+        let modifiers = self
+            .parts
+            .iter()
+            .fold(PartModifiers::default(), |total, part| {
+                total.combine(part.modifiers)
+            });
+        let scale = PartModifiers::scale;
+        let base = &self.base;
+
+        self.muzzle = Muzzle {
+            min_spread: scale(base.muzzle.min_spread, modifiers.spread),
+            max_spread: scale(base.muzzle.max_spread, modifiers.spread),
+            current_spread: self.muzzle.current_spread,
+            max_range: scale(base.muzzle.max_range, modifiers.range),
+            ..base.muzzle.clone()
+        };
+        self.receiver = Receiver {
+            base_damage: scale(base.receiver.base_damage as f32, modifiers.damage).round() as u16,
+            force_transfer: scale(base.receiver.force_transfer, modifiers.force),
+            kick: scale(base.receiver.kick, modifiers.kick),
+            ..base.receiver.clone()
+        };
+        let max = (base.clip.max as i16 + modifiers.clip_size).clamp(1, u8::MAX as i16) as u8;
+        let unloaded = self.clip.current.saturating_sub(max) as u32;
+        self.clip = Clip {
+            max,
+            current: self.clip.current.min(max),
+            loaded: self.clip.loaded,
+            reload_time: scale(base.clip.reload_time, modifiers.reload_time),
+            ..base.clip
+        };
+        let mut trigger = base.trigger.clone();
+        trigger.set_shot_interval(scale(base.trigger.shot_interval(), modifiers.shot_interval));
+        if let Some(mode) = modifiers.trigger_mode {
+            trigger.add_mode(mode);
         }
+        trigger.keep_state_from(&self.trigger);
+        self.trigger = trigger;
+        unloaded
+    }
+
+    pub fn platform(&self) -> &str {
+        &self.base.platform
+    }
+
+    pub fn parts(&self) -> &[GunPart] {
+        &self.parts
+    }
+
+    pub fn part(&self, slot: PartSlot) -> Option<&GunPart> {
+        self.parts.iter().find(|part| part.slot == slot)
+    }
+
+    pub fn can_install(&self, part: &GunPart) -> Result<(), Incompatibility> {
+        part.check_fit(self.platform(), &self.parts)
+    }
+
+    /// Puts `part` in its slot and hands back whatever was there, check `can_install` first
+    pub fn install_part(&mut self, part: GunPart) -> PartChange {
+        let removed = self.take_part(part.slot);
+        self.parts.push(part);
+        PartChange {
+            removed,
+            unloaded: self.assemble(),
+        }
+    }
+
+    pub fn remove_part(&mut self, slot: PartSlot) -> PartChange {
+        let removed = self.take_part(slot);
+        PartChange {
+            removed,
+            unloaded: self.assemble(),
+        }
+    }
+
+    fn take_part(&mut self, slot: PartSlot) -> Option<GunPart> {
+        let index = self.parts.iter().position(|part| part.slot == slot)?;
+        Some(self.parts.remove(index))
+    }
+
+    pub fn stats(&self) -> GunStats {
+        GunStats {
+            damage: self.receiver.base_damage,
+            range: self.muzzle.max_range,
+            spread: self.muzzle.min_spread,
+            kick: self.receiver.kick,
+            clip: self.clip.max,
+            reload_time: self.clip.reload_time,
+            shot_interval: self.trigger.shot_interval(),
+            trigger_mode: self.trigger.get_trigger_mode(),
+        }
+    }
+
+    pub fn definition(&self) -> &Handle<GunDefinition> {
+        &self.definition
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn model(&self) -> &Handle<Scene> {
+        &self.model
+    }
+
+    pub fn shot_sound(&self) -> Option<&Handle<AudioSource>> {
+        self.shot_sound.as_ref()
+    }
+
+    pub fn empty_sound(&self) -> Option<&Handle<AudioSource>> {
+        self.empty_sound.as_ref()
     }
-}
 
-impl Gun {
     pub fn tick(&mut self, delta: Duration) {
         self.trigger.tick(delta);
-        self.reload_timer.tick(delta);
         self.muzzle.reduce_spread();
+        self.tick_reload(delta);
+    }
+
+    /// Reloads finish even while the gun is holstered
+    pub fn tick_reload(&mut self, delta: Duration) {
+        self.reload_timer.tick(delta);
         if self.reload_timer.finished() {
             self.reloading = false;
+            if let Some((ammo, rounds)) = self.reload_pending.take() {
+                self.clip.load(ammo, rounds);
+            }
         }
     }
 
-    pub fn fire(&mut self, camera_focus: Res<CameraFocus>) -> FireResult {
+    /// Fires if the trigger mode says this input should
+    pub fn pull_trigger(&mut self, input: TriggerInput, camera_focus: &CameraFocus) -> FireResult {
+        if self.reloading {
+            self.trigger.interrupt();
+            return FireResult::NoAction;
+        }
+        match self.trigger.pull(input) {
+            Some(power) => self.fire(camera_focus, power),
+            None => FireResult::NoAction,
+        }
+    }
+
+    /// Fires one shot with its damage and force scaled by `power`
+    pub fn fire(&mut self, camera_focus: &CameraFocus, power: f32) -> FireResult {
         if self.trigger.can_fire() && !self.reloading {
             if self.clip.spend_ammo() {
+                let ammo = self.clip.loaded;
+                let base_damage =
+                    (self.receiver.base_damage as f32 * power * ammo.damage()).round() as u16;
+                let force = self.receiver.get_force() * power;
+                let penetration = ammo.penetration();
                 use FireType::*;
                 let shot = match self.receiver.fire_type {
                     Hitscan => ShotEvent::Raycast(vec![RaycastShot {
-                        base_damage: self.receiver.base_damage,
+                        base_damage,
                         range: self.muzzle.get_range(),
-                        force: self.receiver.get_force(),
+                        force,
+                        penetration,
                         dir: camera_focus.forward_randomized(self.muzzle.get_spread()),
                         origin: camera_focus.origin(),
                     }]),
@@ -65,33 +278,26 @@ impl Gun {
 
                         for _ in 1..amount {
                             shots_vec.push(RaycastShot {
-                                base_damage: self.receiver.base_damage,
+                                base_damage,
                                 range: self.muzzle.get_range(),
-                                force: self.receiver.get_force(),
+                                force,
+                                penetration,
                                 dir: camera_focus.forward_randomized(self.muzzle.get_spread()),
                                 origin: camera_focus.origin(),
                             });
                         }
                         ShotEvent::Raycast(shots_vec)
                     }
-                    Projectile => ShotEvent::Projectile(vec![ProjectileShot {
-                        base_damage: self.receiver.base_damage,
-                        speed: 30.0,
-                        force: self.receiver.get_force(),
-                        dir: camera_focus.forward_randomized(self.muzzle.get_spread()),
-                        origin: camera_focus.origin(),
-                    }]),
+                    Projectile => ShotEvent::Projectile(vec![self.projectile_shot(
+                        camera_focus,
+                        base_damage,
+                        force,
+                    )]),
                     ProjectileSpread(amount) => {
                         let mut shots_vec: Vec<ProjectileShot> = Vec::new();
 
                         for _ in 1..amount {
-                            shots_vec.push(ProjectileShot {
-                                base_damage: self.receiver.base_damage,
-                                speed: 30.0,
-                                force: self.receiver.get_force(),
-                                dir: camera_focus.forward_randomized(self.muzzle.get_spread()),
-                                origin: camera_focus.origin(),
-                            });
+                            shots_vec.push(self.projectile_shot(camera_focus, base_damage, force));
                         }
                         ShotEvent::Projectile(shots_vec)
                     }
@@ -100,6 +306,7 @@ impl Gun {
                 self.muzzle.increase_spread();
                 FireResult::Shot(shot)
             } else {
+                self.trigger.interrupt();
                 FireResult::EmptyClip
             }
         } else {
@@ -107,20 +314,100 @@ impl Gun {
         }
     }
 
+    fn projectile_shot(
+        &self,
+        camera_focus: &CameraFocus,
+        base_damage: u16,
+        force: f32,
+    ) -> ProjectileShot {
+        let settings = &self.receiver.projectile;
+        ProjectileShot {
+            base_damage,
+            speed: settings.speed,
+            gravity: settings.gravity,
+            radius: settings.radius,
+            lifetime: settings.lifetime,
+            range: self.muzzle.get_range(),
+            force,
+            penetration: self.clip.loaded.penetration(),
+            dir: camera_focus.forward_randomized(self.muzzle.get_spread()),
+            origin: camera_focus.origin(),
+        }
+    }
+
     pub fn current_ammo(&self) -> u8 {
         self.clip.current
     }
 
-    pub fn reload(&mut self, mut wallet: ResMut<Wallet>) -> Option<crate::money::PopUpCommand> {
-        let percentage_purchased = self.clip.reload();
+    pub fn loaded_ammo_type(&self) -> AmmoType {
+        self.clip.loaded
+    }
+
+    pub fn ammo_type(&self) -> AmmoType {
+        self.ammo_type
+    }
+
+    pub fn caliber(&self) -> Caliber {
+        self.clip.caliber
+    }
+
+    /// Picks what the next reload loads, rounds already in the clip stay until then
+    pub fn select_ammo_type(&mut self, ammo: AmmoType) {
+        self.ammo_type = ammo;
+    }
+
+    /// Fills the clip with the selected ammo type once the reload finishes, drawing rounds from
+    /// `stock` first and buying whatever is missing at the field price, as far as the wallet
+    /// stretches. Rounds of another type go back into stock
+    pub fn reload(&mut self, stock: &mut AmmoStock, wallet: &mut Wallet, date: Date) -> Reload {
+        let caliber = self.clip.caliber;
+        let ammo = self.ammo_type;
+        let switching = self.clip.loaded != ammo && self.clip.current > 0;
+        let needed = if switching {
+            self.clip.max
+        } else {
+            self.clip.max - self.clip.current
+        } as u32;
+        if needed == 0 {
+            return Reload::default();
+        }
+
+        let price = ammo.field_price(caliber);
+        let affordable = if price.is_positive() {
+            (wallet.funds().cents() / price.cents()).max(0) as u32
+        } else {
+            u32::MAX
+        };
+        let from_stock = stock.take(caliber, ammo, needed);
+        let bought = (needed - from_stock).min(affordable);
+        let obtained = from_stock + bought;
+        let short = needed - obtained;
+        if obtained == 0 {
+            return Reload {
+                payment: None,
+                short,
+            };
+        }
 
-        if percentage_purchased > 0.0 {
-            let popup = wallet.debit(self.clip.clip_cost * percentage_purchased);
-            self.reload_timer = Timer::from_seconds(self.clip.get_reload_time(), TimerMode::Once);
-            self.reloading = true;
-            return Some(popup);
+        if switching {
+            stock.add(caliber, self.clip.loaded, self.clip.current as u32);
+            self.clip.current = 0;
+        }
+        self.reload_pending = Some((ammo, obtained as u8));
+        self.reload_timer = Timer::from_seconds(self.clip.get_reload_time(), TimerMode::Once);
+        self.reloading = true;
+
+        Reload {
+            payment: (bought > 0).then(|| {
+                wallet.debit(
+                    price * bought as i64,
+                    TransactionCategory::Ammo,
+                    date,
+                    format!("Reload, {} {} rounds", bought, ammo),
+                )
+            }),
+            short,
         }
-        None
     }
 
     pub fn is_reloading(&self) -> bool {
@@ -131,9 +418,72 @@ impl Gun {
         self.trigger.get_trigger_mode()
     }
 
+    pub fn trigger_modes(&self) -> &[TriggerMode] {
+        &self.trigger.modes
+    }
+
+    /// Switches to the next fire mode the gun supports
+    pub fn cycle_fire_mode(&mut self) -> TriggerMode {
+        self.trigger.cycle_mode()
+    }
+
+    pub fn charge(&self) -> Option<f32> {
+        self.trigger.charge()
+    }
+
+    pub fn is_cycling(&self) -> bool {
+        self.trigger.cycling
+    }
+
     pub fn get_spread(&self) -> f32 {
         self.muzzle.get_spread()
     }
+
+    pub fn recoil(&self) -> &Recoil {
+        &self.receiver.recoil
+    }
+
+    /// Camera kick in degrees up and right for the `shot`th shot of a spray
+    pub fn get_kick(&self, shot: usize) -> Vec2 {
+        self.receiver.get_kick(shot)
+    }
+}
+
+/// What a reload cost, and how far it fell short of a full clip
+#[derive(Default)]
+pub struct Reload {
+    pub payment: Option<WalletNotification>,
+    /// Missing rounds the wallet couldn't pay for
+    pub short: u32,
+}
+
+/// What came off a gun when its parts were changed
+pub struct PartChange {
+    pub removed: Option<GunPart>,
+    /// Loaded rounds that no longer fit in the clip, of the gun's loaded ammo type
+    pub unloaded: u32,
+}
+
+/// A gun's stats after its parts are applied
+#[derive(Clone, Copy, Debug)]
+pub struct GunStats {
+    pub damage: u16,
+    pub range: f32,
+    pub spread: f32,
+    pub kick: f32,
+    pub clip: u8,
+    pub reload_time: f32,
+    pub shot_interval: f32,
+    pub trigger_mode: TriggerMode,
+}
+
+impl fmt::Display for GunStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Damage {}  Range {:.0}m", self.damage, self.range)?;
+        writeln!(f, "Spread {:.1}  Kick {:.1}", self.spread, self.kick)?;
+        writeln!(f, "Clip {}  Reload {:.2}s", self.clip, self.reload_time)?;
+        write!(f, "{} every {:.2}s", self.trigger_mode, self.shot_interval)
+    }
 }
 
 pub enum Shot {
@@ -182,6 +532,7 @@ pub struct RaycastShot {
     pub dir: Vec3,
     pub range: f32,
     pub force: f32,
+    pub penetration: f32,
 }
 
 #[derive(Default)]
@@ -190,29 +541,103 @@ pub struct ProjectileShot {
     pub origin: Vec3,
     pub dir: Vec3,
     pub speed: f32,
+    pub gravity: f32,
+    pub radius: f32,
+    pub lifetime: f32,
+    pub range: f32,
     pub force: f32,
+    pub penetration: f32,
 }
 
-#[derive(Default)]
+#[derive(Deserialize, Clone, Default)]
 pub struct Receiver {
     fire_type: FireType,
     base_damage: u16,
     force_transfer: f32,
     kick: f32,
+    #[serde(default)]
+    recoil: Recoil,
+    #[serde(default)]
+    projectile: ProjectileSettings,
+}
+
+/// How the camera climbs while firing, scaled by the receiver's `kick`
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct Recoil {
+    /// Up and right kick for each shot in a row, the last step repeats
+    pub pattern: Vec<(f32, f32)>,
+    /// Random sideways wobble added to every shot
+    pub jitter: f32,
+    /// Degrees per second the camera settles back towards where it was aimed
+    pub recovery: f32,
+    /// Seconds between shots before the pattern starts over
+    pub reset_time: f32,
+    /// Multiplier while aiming down sights
+    pub sights: f32,
+}
+
+impl Default for Recoil {
+    fn default() -> Self {
+        Recoil {
+            pattern: vec![(1.0, 0.0)],
+            jitter: 0.2,
+            recovery: 10.0,
+            reset_time: 0.3,
+            sights: 0.5,
+        }
+    }
+}
+
+/// How anything fired by a projectile `FireType` flies
+#[derive(Deserialize, Clone)]
+pub struct ProjectileSettings {
+    pub speed: f32,
+    /// Downward acceleration, in units per second squared
+    pub gravity: f32,
+    pub radius: f32,
+    /// Seconds before the projectile disappears if it hasn't hit anything
+    pub lifetime: f32,
+}
+
+impl Default for ProjectileSettings {
+    fn default() -> Self {
+        ProjectileSettings {
+            speed: 30.0,
+            gravity: 9.8,
+            radius: 0.05,
+            lifetime: 5.0,
+        }
+    }
 }
 
 impl Receiver {
     fn get_force(&self) -> f32 {
         self.force_transfer
     }
+
+    fn get_kick(&self, shot: usize) -> Vec2 {
+        let recoil = &self.recoil;
+        let (up, right) = recoil
+            .pattern
+            .get(shot)
+            .or(recoil.pattern.last())
+            .copied()
+            .unwrap_or_default();
+        let wobble = (rand::random::<f32>() * 2.0 - 1.0) * recoil.jitter;
+        Vec2::new(up, right + wobble) * self.kick
+    }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Deserialize, Copy, Clone, Default)]
 pub struct Clip {
     max: u8,
+    #[serde(skip)]
     current: u8,
+    #[serde(skip)]
+    loaded: AmmoType,
     reload_time: f32,
-    clip_cost: Money,
+    caliber: Caliber,
 }
 
 impl Clip {
@@ -236,25 +661,9 @@ impl Clip {
         }
     }
 
-    fn reload(&mut self) -> f32 {
-        if self.max == self.current {
-            0.0
-        } else {
-            let difference = (self.current as f32 - self.max as f32) as f32 * -1.0;
-            self.current = self.max;
-            difference / self.max as f32
-        }
-    }
-}
-
-impl Default for Clip {
-    fn default() -> Self {
-        Clip {
-            current: 30,
-            max: 30,
-            reload_time: 1.0,
-            clip_cost: 10.0.into(),
-        }
+    fn load(&mut self, ammo: AmmoType, rounds: u8) {
+        self.loaded = ammo;
+        self.current = (self.current + rounds).min(self.max);
     }
 }
 
@@ -264,10 +673,11 @@ pub struct ClipStats {
     pub current: u8,
 }
 
-#[derive(Default)]
+#[derive(Deserialize, Clone, Default)]
 pub struct Muzzle {
     min_spread: f32,
     max_spread: f32,
+    #[serde(skip)]
     current_spread: f32,
     bloom: f32,
     max_range: f32,
@@ -297,17 +707,79 @@ impl Muzzle {
     }
 }
 
-#[derive(Default)]
+#[derive(Deserialize, Clone, Default)]
+#[serde(from = "TriggerDefinition")]
 pub struct Trigger {
-    trigger_mode: TriggerMode,
+    /// Every mode fire select cycles through, the first is the default
+    modes: Vec<TriggerMode>,
+    selected: usize,
     shot_timer: Timer,
     pullable: bool,
+    /// How long a `Bolt` trigger takes to work the action after a shot
+    cycle_timer: Timer,
+    cycling: bool,
+    burst_remaining: u8,
+    charge_time: f32,
+    /// How long the trigger has been held for a `Charge` shot
+    charging: Option<Duration>,
+}
+
+/// How a `Trigger` is written in a gun definition
+#[derive(Deserialize)]
+struct TriggerDefinition {
+    mode: TriggerMode,
+    /// Other modes the player can switch to
+    #[serde(default)]
+    alternate_modes: Vec<TriggerMode>,
+    /// Seconds between shots
+    shot_interval: f32,
+    /// Seconds to work a `Bolt` action
+    #[serde(default = "default_cycle_time")]
+    cycle_time: f32,
+    /// Seconds to fully charge a `Charge` shot
+    #[serde(default = "default_charge_time")]
+    charge_time: f32,
+}
+
+fn default_cycle_time() -> f32 {
+    0.8
+}
+
+fn default_charge_time() -> f32 {
+    1.0
+}
+
+impl From<TriggerDefinition> for Trigger {
+    fn from(definition: TriggerDefinition) -> Self {
+        let mut trigger = Trigger {
+            modes: vec![definition.mode],
+            shot_timer: Timer::from_seconds(definition.shot_interval, TimerMode::Repeating),
+            cycle_timer: Timer::from_seconds(definition.cycle_time, TimerMode::Once),
+            charge_time: definition.charge_time,
+            ..default()
+        };
+        for mode in definition.alternate_modes {
+            trigger.add_mode(mode);
+        }
+        trigger
+    }
 }
 
+/// What the player is doing with the trigger this frame
+#[derive(Clone, Copy, Default)]
+pub struct TriggerInput {
+    pub pressed: bool,
+    pub just_pressed: bool,
+    pub just_released: bool,
+}
+
+/// Damage and force multipliers for a tapped and a fully charged `Charge` shot
+const CHARGE_POWER: (f32, f32) = (0.5, 2.0);
+
 impl Trigger {
     pub fn auto() -> Self {
         Trigger {
-            trigger_mode: TriggerMode::Auto,
+            modes: vec![TriggerMode::Auto],
             shot_timer: Timer::from_seconds(0.1, TimerMode::Repeating),
             ..default()
         }
@@ -315,14 +787,14 @@ impl Trigger {
 
     pub fn semi_auto() -> Self {
         Trigger {
-            trigger_mode: TriggerMode::SemiAuto,
+            modes: vec![TriggerMode::SemiAuto],
             shot_timer: Timer::from_seconds(0.3, TimerMode::Repeating),
             ..default()
         }
     }
 
     fn can_fire(&self) -> bool {
-        self.pullable
+        self.pullable && !self.cycling
     }
 
     fn tick(&mut self, delta: Duration) {
@@ -333,25 +805,131 @@ impl Trigger {
                 self.shot_timer.reset();
             }
         }
+        if self.cycling && self.cycle_timer.tick(delta).finished() {
+            self.cycling = false;
+        }
+        if let Some(held) = &mut self.charging {
+            *held += delta;
+        }
     }
 
     fn get_trigger_mode(&self) -> TriggerMode {
-        self.trigger_mode
+        self.modes.get(self.selected).copied().unwrap_or_default()
+    }
+
+    fn shot_interval(&self) -> f32 {
+        self.shot_timer.duration().as_secs_f32()
+    }
+
+    fn set_shot_interval(&mut self, seconds: f32) {
+        self.shot_timer = Timer::from_seconds(seconds, TimerMode::Repeating);
+    }
+
+    fn add_mode(&mut self, mode: TriggerMode) {
+        if !self.modes.contains(&mode) {
+            self.modes.push(mode);
+        }
+    }
+
+    /// Keeps the readiness and selected mode of `old` when the stats are rebuilt
+    fn keep_state_from(&mut self, old: &Trigger) {
+        self.pullable = old.pullable;
+        self.cycling = old.cycling;
+        if let Some(index) = self
+            .modes
+            .iter()
+            .position(|mode| *mode == old.get_trigger_mode())
+        {
+            self.selected = index;
+        }
+    }
+
+    fn cycle_mode(&mut self) -> TriggerMode {
+        self.selected = (self.selected + 1) % self.modes.len().max(1);
+        self.interrupt();
+        self.get_trigger_mode()
+    }
+
+    /// The power of the shot this input fires, if it fires one
+    fn pull(&mut self, input: TriggerInput) -> Option<f32> {
+        match self.get_trigger_mode() {
+            TriggerMode::Auto => (input.pressed && self.can_fire()).then_some(1.0),
+            TriggerMode::SemiAuto | TriggerMode::Bolt => {
+                (input.just_pressed && self.can_fire()).then_some(1.0)
+            }
+            TriggerMode::Burst(count) => {
+                if input.just_pressed && self.burst_remaining == 0 && self.can_fire() {
+                    self.burst_remaining = count;
+                }
+                // The rest of the burst fires whether the trigger is still held or not
+                if self.burst_remaining > 0 && self.can_fire() {
+                    self.burst_remaining -= 1;
+                    Some(1.0)
+                } else {
+                    None
+                }
+            }
+            TriggerMode::Charge => {
+                if input.just_pressed && self.can_fire() {
+                    self.charging = Some(Duration::ZERO);
+                }
+                if input.pressed && !input.just_released {
+                    return None;
+                }
+                let charge = self.charge()?;
+                self.charging = None;
+                Some(CHARGE_POWER.0 + (CHARGE_POWER.1 - CHARGE_POWER.0) * charge)
+            }
+        }
+    }
+
+    /// How far a `Charge` shot has charged, from 0 to 1
+    fn charge(&self) -> Option<f32> {
+        self.charging
+            .map(|held| (held.as_secs_f32() / self.charge_time).min(1.0))
     }
 
     fn fire(&mut self) {
         self.pullable = false;
+        if self.get_trigger_mode() == TriggerMode::Bolt {
+            self.cycling = true;
+            self.cycle_timer.reset();
+        }
+    }
+
+    /// Drops a burst or charge that can't be fired
+    fn interrupt(&mut self) {
+        self.burst_remaining = 0;
+        self.charging = None;
     }
 }
 
-#[derive(Clone, Copy, Default)]
+#[derive(Deserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
 pub enum TriggerMode {
     #[default]
     Auto,
     SemiAuto,
+    /// Fires this many shots for every pull
+    Burst(u8),
+    /// Hold to power up the shot, it fires on release
+    Charge,
+    /// Works a bolt or pump after every shot before it can fire again
+    Bolt,
 }
 
-#[derive(Default)]
+impl fmt::Display for TriggerMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TriggerMode::Auto => write!(f, "Auto"),
+            TriggerMode::SemiAuto => write!(f, "Semi"),
+            TriggerMode::Burst(count) => write!(f, "Burst {}", count),
+            TriggerMode::Charge => write!(f, "Charge"),
+            TriggerMode::Bolt => write!(f, "Bolt"),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Default)]
 pub enum FireType {
     #[default]
     Hitscan,
@@ -0,0 +1,301 @@
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+
+use crate::{
+    ammo::AmmoStock,
+    clock::Phone,
+    input::PlayerAction,
+    inventory::Belt,
+    money::{Money, TransactionCategory, Wallet},
+    notifications::{Notification, PostNotification},
+    parts::{GunPart, GunPartLoader, PartSlot},
+    player::Player,
+    GameState, PlayerSet,
+};
+
+pub struct WorkbenchPlugin;
+
+impl Plugin for WorkbenchPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<GunPart>()
+            .init_asset_loader::<GunPartLoader>()
+            .add_state::<WorkbenchState>()
+            .add_event::<UseWorkbench>()
+            .add_event::<TakePart>()
+            .init_resource::<PartsBin>()
+            .init_resource::<WorkbenchCursor>()
+            .configure_set(
+                Update,
+                PlayerSet::Combat.run_if(in_state(WorkbenchState::Closed)),
+            )
+            .configure_set(
+                Update,
+                PlayerSet::Movement.run_if(in_state(WorkbenchState::Closed)),
+            )
+            .add_systems(OnEnter(WorkbenchState::Open), spawn_workbench_ui)
+            .add_systems(OnExit(WorkbenchState::Open), despawn_workbench_ui)
+            .add_systems(
+                Update,
+                (
+                    take_parts,
+                    open_workbench.run_if(in_state(WorkbenchState::Closed)),
+                    (workbench_input, update_workbench_ui)
+                        .chain()
+                        .run_if(in_state(WorkbenchState::Open)),
+                )
+                    .run_if(in_state(GameState::RunAndGun)),
+            );
+    }
+}
+
+#[derive(States, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WorkbenchState {
+    #[default]
+    Closed,
+    Open,
+}
+
+/// Sent when the player interacts with a workbench
+#[derive(Event)]
+pub struct UseWorkbench;
+
+/// Sent when the player interacts with a `PartPickup`
+#[derive(Event)]
+pub struct TakePart(pub Entity);
+
+/// A part lying around in the world, free to take unless it has a price
+#[derive(Component)]
+pub struct PartPickup {
+    pub part: Handle<GunPart>,
+    pub price: Money,
+}
+
+/// Parts the player owns that aren't installed on a gun
+#[derive(Resource, Default)]
+pub struct PartsBin(pub Vec<GunPart>);
+
+impl PartsBin {
+    pub fn for_slot(&self, slot: PartSlot) -> impl Iterator<Item = (usize, &GunPart)> {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(move |(_, part)| part.slot == slot)
+    }
+}
+
+/// Which slot is being worked on, and which choice for it is highlighted. Choice 0 is the
+/// stock part, the rest are parts in the bin for that slot
+#[derive(Resource, Default)]
+struct WorkbenchCursor {
+    slot: usize,
+    choice: usize,
+}
+
+impl WorkbenchCursor {
+    fn slot(&self) -> PartSlot {
+        PartSlot::ALL[self.slot]
+    }
+}
+
+#[derive(Component)]
+struct WorkbenchUi;
+
+#[derive(Component)]
+struct WorkbenchText;
+
+fn take_parts(
+    mut commands: Commands,
+    mut take_events: EventReader<TakePart>,
+    mut bin: ResMut<PartsBin>,
+    mut wallet: ResMut<Wallet>,
+    phone: Res<Phone>,
+    parts: Res<Assets<GunPart>>,
+    pickup_query: Query<&PartPickup>,
+) {
+    for TakePart(entity) in take_events.iter() {
+        let Some((pickup, part)) = pickup_query
+            .get(*entity)
+            .ok()
+            .and_then(|pickup| Some((pickup, parts.get(&pickup.part)?)))
+        else {
+            continue;
+        };
+
+        if !pickup.price.is_zero() {
+            if wallet.funds() < pickup.price {
+                commands.add(PostNotification(Notification::new(
+                    "Can't afford",
+                    format!("{} costs {}", part.name, pickup.price),
+                )));
+                continue;
+            }
+            commands.add(wallet.debit(
+                pickup.price,
+                TransactionCategory::Other,
+                phone.date,
+                part.name.clone(),
+            ));
+        }
+
+        commands.add(PostNotification(Notification::new(
+            "New part",
+            format!("{} ({}), install it at a workbench", part.name, part.slot),
+        )));
+        bin.0.push(part.clone());
+        commands.entity(*entity).despawn_recursive();
+    }
+}
+
+fn open_workbench(
+    mut use_events: EventReader<UseWorkbench>,
+    mut cursor: ResMut<WorkbenchCursor>,
+    mut next_state: ResMut<NextState<WorkbenchState>>,
+) {
+    if use_events.iter().last().is_some() {
+        *cursor = WorkbenchCursor::default();
+        next_state.set(WorkbenchState::Open);
+    }
+}
+
+fn workbench_input(
+    player_query: Query<&ActionState<PlayerAction>, With<Player>>,
+    mut cursor: ResMut<WorkbenchCursor>,
+    mut belt: ResMut<Belt>,
+    mut bin: ResMut<PartsBin>,
+    mut stock: ResMut<AmmoStock>,
+    mut next_state: ResMut<NextState<WorkbenchState>>,
+) {
+    let Ok(action) = player_query.get_single() else {
+        return;
+    };
+
+    if action.just_pressed(PlayerAction::PhoneBack) || action.just_pressed(PlayerAction::Interact) {
+        next_state.set(WorkbenchState::Closed);
+        return;
+    }
+
+    let slot_count = PartSlot::ALL.len();
+    if action.just_pressed(PlayerAction::PhoneLeft) {
+        cursor.slot = (cursor.slot + slot_count - 1) % slot_count;
+        cursor.choice = 0;
+    }
+    if action.just_pressed(PlayerAction::PhoneRight) {
+        cursor.slot = (cursor.slot + 1) % slot_count;
+        cursor.choice = 0;
+    }
+
+    let choices = bin.for_slot(cursor.slot()).count() + 1;
+    if action.just_pressed(PlayerAction::PhoneUp) {
+        cursor.choice = cursor.choice.saturating_sub(1);
+    }
+    if action.just_pressed(PlayerAction::PhoneDown) {
+        cursor.choice = (cursor.choice + 1).min(choices - 1);
+    }
+
+    if !action.just_pressed(PlayerAction::PhoneSelect) || belt.is_empty() {
+        return;
+    }
+
+    let gun = belt.gun_mut();
+    if cursor.choice == 0 {
+        let change = gun.remove_part(cursor.slot());
+        stock.add(gun.caliber(), gun.loaded_ammo_type(), change.unloaded);
+        bin.0.extend(change.removed);
+        return;
+    }
+
+    let Some(index) = bin
+        .for_slot(cursor.slot())
+        .nth(cursor.choice - 1)
+        .map(|(index, _)| index)
+    else {
+        return;
+    };
+    if gun.can_install(&bin.0[index]).is_ok() {
+        let part = bin.0.remove(index);
+        let change = gun.install_part(part);
+        stock.add(gun.caliber(), gun.loaded_ammo_type(), change.unloaded);
+        bin.0.extend(change.removed);
+        cursor.choice = 0;
+    }
+}
+
+fn spawn_workbench_ui(mut commands: Commands) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(15.0),
+                left: Val::Percent(25.0),
+                width: Val::Percent(50.0),
+                padding: UiRect::all(Val::Px(20.0)),
+                ..default()
+            },
+            background_color: Color::rgba(0.05, 0.05, 0.05, 0.85).into(),
+            z_index: ZIndex::Global(40),
+            ..default()
+        })
+        .insert(WorkbenchUi)
+        .with_children(|parent| {
+            parent
+                .spawn(TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 22.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ))
+                .insert(WorkbenchText);
+        });
+}
+
+fn despawn_workbench_ui(mut commands: Commands, ui_query: Query<Entity, With<WorkbenchUi>>) {
+    for entity in &ui_query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn update_workbench_ui(
+    cursor: Res<WorkbenchCursor>,
+    belt: Res<Belt>,
+    bin: Res<PartsBin>,
+    mut text_query: Query<&mut Text, With<WorkbenchText>>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+    let gun = belt.gun();
+
+    let mut lines = vec![
+        format!("{} ({})", gun.name(), gun.platform()),
+        String::new(),
+    ];
+    for (index, slot) in PartSlot::ALL.iter().enumerate() {
+        let cursor_mark = if index == cursor.slot { ">" } else { " " };
+        let installed = gun.part(*slot).map_or("Stock", |part| part.name.as_str());
+        lines.push(format!("{} {}: {}", cursor_mark, slot, installed));
+    }
+
+    lines.push(String::new());
+    let stock_mark = if cursor.choice == 0 { ">" } else { " " };
+    lines.push(format!("{} Stock {}", stock_mark, cursor.slot()));
+    for (choice, (_, part)) in bin.for_slot(cursor.slot()).enumerate() {
+        let mark = if choice + 1 == cursor.choice {
+            ">"
+        } else {
+            " "
+        };
+        let fit = match gun.can_install(part) {
+            Ok(()) => part.modifiers.describe().join(", "),
+            Err(reason) => reason.to_string(),
+        };
+        lines.push(format!("{} {}: {}", mark, part.name, fit));
+    }
+
+    lines.push(String::new());
+    lines.push(gun.stats().to_string());
+    lines.push(String::new());
+    lines.push("[</>] Slot  [^/v] Part  [Select] Install  [Back] Leave".to_string());
+    text.sections[0].value = lines.join("\n");
+}